@@ -1,11 +1,27 @@
+pub(crate) mod code_scanning_alert;
+pub(crate) mod code_size;
+pub(crate) mod codeowner;
+pub(crate) mod commit;
+pub(crate) mod commit_convention_stat;
+pub(crate) mod commit_stat;
+pub(crate) mod dependency;
 mod discussion;
 pub(crate) mod discussion_stat;
+pub(crate) mod history;
+pub(crate) mod hotspot;
 pub(crate) mod issue;
 pub(crate) mod issue_stat;
+pub(crate) mod ownership;
 pub(crate) mod pull_request;
 pub(crate) mod pull_request_stat;
+pub(crate) mod repository;
+pub(crate) mod security_stat;
+mod snapshot;
+pub(crate) mod team;
+pub(crate) mod todo;
+pub(crate) mod vulnerability_alert;
 
-use std::fmt::Display;
+use std::sync::Arc;
 
 use async_graphql::{
     types::connection::{Connection, Edge, EmptyFields},
@@ -16,7 +32,10 @@ use base64::{engine::general_purpose, Engine as _};
 use jiff::Timestamp;
 
 pub(crate) use self::discussion::Discussion;
-use crate::database::Database;
+use crate::{
+    database::Database,
+    settings::{Repository as RepoInfo, Team as TeamInfo},
+};
 
 // The title of the github project.
 pub(crate) const TODO_LIST_PROJECT_TITLE: &str = "to-do list";
@@ -31,12 +50,27 @@ const DEFAULT_PAGE_SIZE: usize = 100;
 /// This is exposed only for [`Schema`], and not used directly.
 #[derive(Default, MergedObject)]
 pub(crate) struct Query(
+    code_scanning_alert::CodeScanningAlertQuery,
+    code_size::CodeSizeQuery,
+    codeowner::CodeownerQuery,
+    commit::CommitQuery,
+    commit_convention_stat::CommitConventionStatQuery,
+    commit_stat::CommitStatQuery,
+    dependency::DependencyQuery,
     discussion::DiscussionQuery,
     discussion_stat::DiscussionStatQuery,
+    hotspot::HotspotQuery,
     issue::IssueQuery,
     issue_stat::IssueStatQuery,
+    ownership::OwnershipQuery,
     pull_request::PullRequestQuery,
     pull_request_stat::PullRequestStatQuery,
+    repository::RepositoryQuery,
+    security_stat::SecurityStatQuery,
+    snapshot::SnapshotQuery,
+    team::TeamQuery,
+    todo::TodoQuery,
+    vulnerability_alert::VulnerabilityAlertQuery,
 );
 
 pub(crate) type Schema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
@@ -58,28 +92,39 @@ impl ScalarType for DateTimeUtc {
     }
 }
 
+/// A node of a connection, whose cursor is its key in the database.
+pub(crate) trait CursorKey {
+    fn cursor_key(&self) -> Vec<u8>;
+}
+
 fn connect_cursor<T>(
     select_vec: Vec<T>,
     prev: bool,
     next: bool,
 ) -> Connection<String, T, EmptyFields, EmptyFields>
 where
-    T: OutputType + Display,
+    T: OutputType + CursorKey,
 {
     let mut connection: Connection<String, T, EmptyFields, EmptyFields> =
         Connection::new(prev, next);
     for output in select_vec {
         connection.edges.push(Edge::new(
-            general_purpose::STANDARD.encode(format!("{output}")),
+            general_purpose::STANDARD.encode(output.cursor_key()),
             output,
         ));
     }
     connection
 }
 
-pub(crate) fn schema(database: Database) -> Schema {
+pub(crate) fn schema(
+    database: Database,
+    repositories: Arc<Vec<RepoInfo>>,
+    teams: Arc<Vec<TeamInfo>>,
+) -> Schema {
     Schema::build(Query::default(), EmptyMutation, EmptySubscription)
         .data(database)
+        .data(repositories)
+        .data(teams)
         .finish()
 }
 
@@ -92,7 +137,7 @@ fn load_connection<N, I>(
     last: Option<usize>,
 ) -> Result<Connection<String, N, EmptyFields, EmptyFields>>
 where
-    N: Display + OutputType,
+    N: CursorKey + OutputType,
     I: DoubleEndedIterator<Item = anyhow::Result<N>>,
 {
     let db = ctx.data::<Database>()?;
@@ -165,9 +210,21 @@ struct TestSchema {
 #[cfg(test)]
 impl TestSchema {
     fn new() -> Self {
+        Self::with_settings(Vec::new(), Vec::new())
+    }
+
+    fn with_repositories(repositories: Vec<RepoInfo>) -> Self {
+        Self::with_settings(repositories, Vec::new())
+    }
+
+    fn with_teams(teams: Vec<TeamInfo>) -> Self {
+        Self::with_settings(Vec::new(), teams)
+    }
+
+    fn with_settings(repositories: Vec<RepoInfo>, teams: Vec<TeamInfo>) -> Self {
         let db_dir = tempfile::tempdir().unwrap();
         let db = Database::connect(db_dir.path()).unwrap();
-        let schema = schema(db.clone());
+        let schema = schema(db.clone(), Arc::new(repositories), Arc::new(teams));
         Self {
             _dir: db_dir,
             db,
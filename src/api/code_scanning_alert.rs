@@ -0,0 +1,187 @@
+use anyhow::Context as AnyhowContext;
+use async_graphql::{
+    connection::{query, Connection, EmptyFields},
+    Context, Enum, Object, Result, SimpleObject,
+};
+
+use crate::{
+    api::{self, DateTimeUtc},
+    database::{
+        self,
+        code_scanning_alert::{
+            CodeScanningAlertState as CodeScanningAlertStateDbSchema, GitHubCodeScanningAlert,
+        },
+        Database, TryFromKeyValue,
+    },
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum CodeScanningAlertState {
+    Open,
+    Dismissed,
+    Fixed,
+}
+
+impl From<CodeScanningAlertStateDbSchema> for CodeScanningAlertState {
+    fn from(state: CodeScanningAlertStateDbSchema) -> Self {
+        match state {
+            CodeScanningAlertStateDbSchema::Open => Self::Open,
+            CodeScanningAlertStateDbSchema::Dismissed => Self::Dismissed,
+            CodeScanningAlertStateDbSchema::Fixed => Self::Fixed,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CodeScanningAlert {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+    /// The ID of the rule that raised the alert.
+    pub(crate) rule: String,
+    /// The name of the tool that ran the rule.
+    pub(crate) tool: String,
+    /// The security severity of the rule, such as `high`, or `None` if the
+    /// rule is not a security rule.
+    pub(crate) security_severity: Option<String>,
+    pub(crate) state: CodeScanningAlertState,
+    pub(crate) created_at: DateTimeUtc,
+    pub(crate) fixed_at: Option<DateTimeUtc>,
+    pub(crate) dismissed_at: Option<DateTimeUtc>,
+}
+
+impl TryFromKeyValue for CodeScanningAlert {
+    fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
+        let (owner, repo, number) = database::parse_key(key)
+            .with_context(|| format!("invalid key in database: {key:02x?}"))?;
+        let alert: GitHubCodeScanningAlert = database::decode(value)
+            .with_context(|| format!("Deserialization failed for value: {value:?}"))?;
+        Ok(Self {
+            owner,
+            repo,
+            number,
+            rule: alert.rule,
+            tool: alert.tool,
+            security_severity: alert.security_severity,
+            state: alert.state.into(),
+            created_at: DateTimeUtc(alert.created_at),
+            fixed_at: alert.fixed_at.map(DateTimeUtc),
+            dismissed_at: alert.dismissed_at.map(DateTimeUtc),
+        })
+    }
+}
+
+impl api::CursorKey for CodeScanningAlert {
+    fn cursor_key(&self) -> Vec<u8> {
+        database::record_key(&self.owner, &self.repo, self.number)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct CodeScanningAlertQuery;
+
+#[Object]
+impl CodeScanningAlertQuery {
+    /// Returns the code scanning alerts of the configured repositories.
+    async fn code_scanning_alerts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<String, CodeScanningAlert, EmptyFields, EmptyFields>> {
+        query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                api::load_connection(
+                    ctx,
+                    Database::code_scanning_alerts,
+                    after,
+                    before,
+                    first,
+                    last,
+                )
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::code_scanning_alert::{CodeScanningAlertState, GitHubCodeScanningAlert},
+    };
+
+    #[tokio::test]
+    async fn code_scanning_alerts_first() {
+        let schema = TestSchema::new();
+        let alerts = vec![
+            GitHubCodeScanningAlert {
+                number: 1,
+                rule: "rust/hard-coded-cryptographic-value".to_string(),
+                tool: "CodeQL".to_string(),
+                security_severity: Some("high".to_string()),
+                state: CodeScanningAlertState::Fixed,
+                created_at: "2025-01-01T00:00:00Z".parse().unwrap(),
+                fixed_at: Some("2025-01-03T00:00:00Z".parse().unwrap()),
+                dismissed_at: None,
+            },
+            GitHubCodeScanningAlert {
+                number: 2,
+                rule: "rust/unused-variable".to_string(),
+                tool: "CodeQL".to_string(),
+                security_severity: None,
+                state: CodeScanningAlertState::Open,
+                created_at: "2025-01-02T00:00:00Z".parse().unwrap(),
+                fixed_at: None,
+                dismissed_at: None,
+            },
+        ];
+        schema
+            .db
+            .insert_code_scanning_alerts(alerts, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r"
+        {
+            codeScanningAlerts(first: 1) {
+                edges {
+                    node {
+                        repo
+                        number
+                        rule
+                        securitySeverity
+                        state
+                        fixedAt
+                    }
+                }
+                pageInfo {
+                    hasNextPage
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["codeScanningAlerts"],
+            serde_json::json!({
+                "edges": [{
+                    "node": {
+                        "repo": "github-dashboard-server",
+                        "number": 1,
+                        "rule": "rust/hard-coded-cryptographic-value",
+                        "securitySeverity": "high",
+                        "state": "FIXED",
+                        "fixedAt": "2025-01-03T00:00:00Z"
+                    }
+                }],
+                "pageInfo": { "hasNextPage": true }
+            })
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+use crate::{api::DateTimeUtc, database::Database};
+
+#[derive(SimpleObject)]
+pub(crate) struct LanguageCodeSize {
+    pub(crate) language: String,
+    /// The line counts of the language, oldest first.
+    pub(crate) series: Vec<CodeSizePoint>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CodeSizePoint {
+    pub(crate) taken_at: DateTimeUtc,
+    pub(crate) files: usize,
+    pub(crate) code: usize,
+    pub(crate) comment: usize,
+    pub(crate) blank: usize,
+}
+
+#[derive(Default)]
+pub(super) struct CodeSizeQuery;
+
+#[Object]
+impl CodeSizeQuery {
+    /// Returns the daily line counts of the repository per language.
+    ///
+    /// `begin` (inclusive) and `end` (exclusive) limit the snapshots.
+    #[allow(clippy::unused_async)]
+    async fn code_size(
+        &self,
+        ctx: &Context<'_>,
+        owner: String,
+        repo: String,
+        begin: Option<DateTimeUtc>,
+        end: Option<DateTimeUtc>,
+    ) -> Result<Vec<LanguageCodeSize>> {
+        let db = ctx.data::<Database>()?;
+        let mut languages: BTreeMap<String, Vec<CodeSizePoint>> = BTreeMap::new();
+        for snapshot in db.code_size_snapshots(&owner, &repo)? {
+            let taken_at = DateTimeUtc(snapshot.taken_at);
+            if begin.is_some_and(|begin| taken_at < begin) || end.is_some_and(|end| taken_at >= end)
+            {
+                continue;
+            }
+            for count in snapshot.languages {
+                languages
+                    .entry(count.language)
+                    .or_default()
+                    .push(CodeSizePoint {
+                        taken_at,
+                        files: count.files,
+                        code: count.code,
+                        comment: count.comment,
+                        blank: count.blank,
+                    });
+            }
+        }
+        Ok(languages
+            .into_iter()
+            .map(|(language, series)| LanguageCodeSize { language, series })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::code_size::{CodeSizeSnapshot, LanguageLineCount},
+    };
+
+    fn line_count(language: &str, code: usize) -> LanguageLineCount {
+        LanguageLineCount {
+            language: language.to_string(),
+            files: 1,
+            code,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn code_size_series_per_language() {
+        let schema = TestSchema::new();
+        let snapshots = [
+            ("2025-01-04T09:00:00Z", vec![line_count("Rust", 90)]),
+            ("2025-01-05T09:00:00Z", vec![line_count("Rust", 100)]),
+            (
+                "2025-01-06T09:00:00Z",
+                vec![line_count("Rust", 120), line_count("TOML", 10)],
+            ),
+        ];
+        for (taken_at, languages) in snapshots {
+            schema
+                .db
+                .insert_code_size_snapshot(
+                    CodeSizeSnapshot {
+                        taken_at: taken_at.parse().unwrap(),
+                        languages,
+                    },
+                    "aicers",
+                    "github-dashboard-server",
+                )
+                .unwrap();
+        }
+
+        let query = r#"
+        {
+            codeSize(owner: "aicers", repo: "github-dashboard-server", begin: "2025-01-05T00:00:00Z") {
+                language
+                series {
+                    takenAt
+                    code
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["codeSize"],
+            serde_json::json!([
+                {
+                    "language": "Rust",
+                    "series": [
+                        { "takenAt": "2025-01-05T09:00:00Z", "code": 100 },
+                        { "takenAt": "2025-01-06T09:00:00Z", "code": 120 }
+                    ]
+                },
+                {
+                    "language": "TOML",
+                    "series": [{ "takenAt": "2025-01-06T09:00:00Z", "code": 10 }]
+                }
+            ])
+        );
+    }
+}
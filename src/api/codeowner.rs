@@ -0,0 +1,452 @@
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
+
+use anyhow::Context as AnyhowContext;
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+use num_traits::ToPrimitive;
+use regex::Regex;
+
+use crate::{
+    api::{pull_request::PullRequest, team, DateTimeUtc},
+    database::{codeowners::CodeownerRule, Database},
+    outbound::pull_requests::{PullRequestReviewState, PullRequestState},
+};
+
+/// Converts a gitignore-style `CODEOWNERS` pattern into a regular expression
+/// matching the paths it applies to.
+fn pattern_regex(pattern: &str) -> anyhow::Result<Regex> {
+    let body = pattern.trim_start_matches('/').trim_end_matches('/');
+    // A pattern with a slash other than a trailing one is relative to the
+    // root; otherwise it matches at any depth.
+    let anchored = pattern.starts_with('/') || body.contains('/');
+    let mut expr = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    expr.push_str("(?:.*/)?");
+                } else {
+                    expr.push_str(".*");
+                }
+            }
+            '*' => expr.push_str("[^/]*"),
+            '?' => expr.push_str("[^/]"),
+            _ => expr.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    if pattern.ends_with('/') {
+        // A directory pattern matches only what is under the directory.
+        expr.push_str("/.*");
+    } else if !body.ends_with('*') {
+        // A pattern naming a directory also matches everything under it, but
+        // `docs/*` matches only the direct children of `docs`.
+        expr.push_str("(?:/.*)?");
+    }
+    expr.push('$');
+    Regex::new(&expr).with_context(|| format!("invalid CODEOWNERS pattern: {pattern}"))
+}
+
+/// The compiled rules of a `CODEOWNERS` file.
+struct Codeowners(Vec<(Regex, Vec<String>)>);
+
+impl Codeowners {
+    fn new(rules: Vec<CodeownerRule>) -> anyhow::Result<Self> {
+        rules
+            .into_iter()
+            .map(|rule| Ok((pattern_regex(&rule.pattern)?, rule.owners)))
+            .collect::<anyhow::Result<_>>()
+            .map(Self)
+    }
+
+    /// Returns the owners of `path`. The last matching rule takes precedence,
+    /// as on GitHub.
+    fn owners(&self, path: &str) -> &[String] {
+        self.0
+            .iter()
+            .rev()
+            .find(|(regex, _)| regex.is_match(path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Resolves the `@user` and `@org/team` owners into the logins of the users.
+///
+/// A team defined only in the configuration file matches `@org/team` for any
+/// organization. Email owners cannot be matched against reviewers and are
+/// ignored.
+struct OwnerResolver(HashMap<String, HashSet<String>>);
+
+impl OwnerResolver {
+    fn new(teams: Vec<team::Team>) -> Self {
+        let mut members: HashMap<String, HashSet<String>> = HashMap::new();
+        for team in teams {
+            let name = match &team.organization {
+                Some(organization) => format!("{organization}/{}", team.slug),
+                None => team.slug,
+            };
+            members
+                .entry(name.to_lowercase())
+                .or_default()
+                .extend(team.members);
+        }
+        Self(members)
+    }
+
+    fn is_owner(&self, owner: &str, login: &str) -> bool {
+        let Some(name) = owner.strip_prefix('@') else {
+            return false;
+        };
+        let name = name.to_lowercase();
+        match name.split_once('/') {
+            Some((_, slug)) => self
+                .0
+                .get(&name)
+                .or_else(|| self.0.get(slug))
+                .is_some_and(|members| members.contains(login)),
+            None => name.eq_ignore_ascii_case(login),
+        }
+    }
+}
+
+#[derive(InputObject, Debug)]
+pub(crate) struct CodeownerComplianceFilter {
+    /// Filter by repository name.
+    repo: Option<String>,
+    /// Start of the merge datetime range. (inclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    begin: Option<DateTimeUtc>,
+    /// End of the merge datetime range. (exclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    end: Option<DateTimeUtc>,
+}
+
+impl CodeownerComplianceFilter {
+    fn matches(&self, pr: &PullRequest) -> bool {
+        let Some(merged_at) = pr.merged_at else {
+            return false;
+        };
+        matches!(pr.state, PullRequestState::MERGED)
+            && self.repo.as_ref().is_none_or(|repo| pr.repo == *repo)
+            && self.begin.as_ref().is_none_or(|begin| merged_at >= *begin)
+            && self.end.as_ref().is_none_or(|end| merged_at < *end)
+    }
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CodeownerCompliance {
+    /// The compliance of each repository with a `CODEOWNERS` file, ordered by
+    /// owner and name.
+    repositories: Vec<RepositoryCompliance>,
+    /// The merged pull requests that changed an owned path without the
+    /// approval of any of its owners.
+    violations: Vec<CodeownerViolation>,
+}
+
+#[derive(SimpleObject)]
+struct RepositoryCompliance {
+    owner: String,
+    repo: String,
+    /// The number of merged pull requests that changed an owned path.
+    owned_pr_count: usize,
+    /// The number of those pull requests approved by the owners of every
+    /// owned path they changed.
+    compliant_pr_count: usize,
+    /// `compliantPrCount` divided by `ownedPrCount`, or `None` if no merged
+    /// pull request changed an owned path.
+    compliance_rate: Option<f64>,
+}
+
+#[derive(SimpleObject)]
+struct CodeownerViolation {
+    owner: String,
+    repo: String,
+    number: i32,
+    title: String,
+    url: String,
+    merged_at: DateTimeUtc,
+    /// The owned paths no owner approved a change to.
+    paths: Vec<UnapprovedPath>,
+}
+
+#[derive(SimpleObject)]
+struct UnapprovedPath {
+    path: String,
+    owners: Vec<String>,
+}
+
+#[derive(Default)]
+pub(super) struct CodeownerQuery;
+
+#[Object]
+impl CodeownerQuery {
+    /// Checks whether the merged pull requests were approved by the code
+    /// owners of the paths they changed, according to the `CODEOWNERS` file
+    /// of the checked-out branch.
+    ///
+    /// Only the first 100 changed files of each pull request are checked.
+    #[allow(clippy::unused_async)]
+    async fn codeowner_compliance(
+        &self,
+        ctx: &Context<'_>,
+        filter: CodeownerComplianceFilter,
+    ) -> Result<CodeownerCompliance> {
+        let db = ctx.data::<Database>()?;
+        let resolver = OwnerResolver::new(team::load_teams(ctx)?);
+        let mut codeowners: HashMap<(String, String), Option<Codeowners>> = HashMap::new();
+        let mut counts: BTreeMap<(String, String), (usize, usize)> = BTreeMap::new();
+        let mut violations = Vec::new();
+        for pr in db.pull_requests(None, None) {
+            let pr = pr?;
+            if !filter.matches(&pr) {
+                continue;
+            }
+            let key = (pr.owner.clone(), pr.repo.clone());
+            let rules = match codeowners.entry(key.clone()) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    db.codeowners(&pr.owner, &pr.repo)?
+                        .filter(|rules| !rules.is_empty())
+                        .map(Codeowners::new)
+                        .transpose()?,
+                ),
+            };
+            let Some(rules) = rules else {
+                continue;
+            };
+            let approvers: Vec<&str> = pr
+                .reviews
+                .iter()
+                .filter(|review| matches!(review.state, PullRequestReviewState::APPROVED))
+                .map(|review| review.author.as_str())
+                .collect();
+            let mut owned = false;
+            let mut paths = Vec::new();
+            for path in &pr.files {
+                let owners = rules.owners(path);
+                if owners.is_empty() {
+                    continue;
+                }
+                owned = true;
+                if !owners.iter().any(|owner| {
+                    approvers
+                        .iter()
+                        .any(|approver| resolver.is_owner(owner, approver))
+                }) {
+                    paths.push(UnapprovedPath {
+                        path: path.clone(),
+                        owners: owners.to_vec(),
+                    });
+                }
+            }
+            if !owned {
+                continue;
+            }
+            let count = counts.entry(key).or_default();
+            count.0 += 1;
+            if paths.is_empty() {
+                count.1 += 1;
+            } else if let Some(merged_at) = pr.merged_at {
+                violations.push(CodeownerViolation {
+                    owner: pr.owner,
+                    repo: pr.repo,
+                    number: pr.number,
+                    title: pr.title,
+                    url: pr.url,
+                    merged_at,
+                    paths,
+                });
+            }
+        }
+        let repositories = counts
+            .into_iter()
+            .map(|((owner, repo), (owned_pr_count, compliant_pr_count))| {
+                let compliance_rate = if owned_pr_count == 0 {
+                    None
+                } else {
+                    Some(
+                        compliant_pr_count
+                            .to_f64()
+                            .context("Failed to convert usize to f64")?
+                            / owned_pr_count
+                                .to_f64()
+                                .context("Failed to convert usize to f64")?,
+                    )
+                };
+                Ok(RepositoryCompliance {
+                    owner,
+                    repo,
+                    owned_pr_count,
+                    compliant_pr_count,
+                    compliance_rate,
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(CodeownerCompliance {
+            repositories,
+            violations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pattern_regex;
+    use crate::{
+        api::TestSchema,
+        database::{
+            codeowners::CodeownerRule,
+            pull_request::{
+                GitHubPRCommentConnection, GitHubPullRequestNode, GitHubReviewConnection,
+                RepositoryNode, ReviewNode,
+            },
+        },
+        outbound::pull_requests::{PullRequestReviewState, PullRequestState},
+        settings::Team,
+    };
+
+    #[test]
+    fn match_patterns() {
+        let cases = [
+            ("*", "src/main.rs", true),
+            ("*.rs", "src/api/team.rs", true),
+            ("*.rs", "README.md", false),
+            ("/docs/", "docs/guide/intro.md", true),
+            ("/docs/", "src/docs/intro.md", false),
+            ("docs/", "src/docs/intro.md", true),
+            ("docs/*", "docs/intro.md", true),
+            ("docs/*", "docs/guide/intro.md", false),
+            ("src/api", "src/api/team.rs", true),
+            ("src/api", "lib/src/api/team.rs", false),
+            ("**/logs", "deep/path/logs/a.log", true),
+            ("/build/**/*.o", "build/a/b/c.o", true),
+            ("/Cargo.toml", "Cargo.toml", true),
+            ("/Cargo.toml", "crates/Cargo.toml", false),
+        ];
+        for (pattern, path, expected) in cases {
+            assert_eq!(
+                pattern_regex(pattern).unwrap().is_match(path),
+                expected,
+                "{pattern} {path}"
+            );
+        }
+    }
+
+    fn rule(pattern: &str, owners: &[&str]) -> CodeownerRule {
+        CodeownerRule {
+            pattern: pattern.to_string(),
+            owners: owners.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn merged_pr(number: i32, files: &[&str], approvers: &[&str]) -> GitHubPullRequestNode {
+        GitHubPullRequestNode {
+            number,
+            state: PullRequestState::MERGED,
+            merged_at: Some("2025-01-10T00:00:00Z".parse().unwrap()),
+            files: files.iter().map(ToString::to_string).collect(),
+            repository: RepositoryNode {
+                owner: "aicers".to_string(),
+                name: "github-dashboard-server".to_string(),
+            },
+            reviews: GitHubReviewConnection {
+                total_count: approvers.len().try_into().unwrap(),
+                nodes: approvers
+                    .iter()
+                    .map(|approver| ReviewNode {
+                        author: (*approver).to_string(),
+                        state: PullRequestReviewState::APPROVED,
+                        body: None,
+                        url: String::new(),
+                        created_at: "2025-01-09T00:00:00Z".parse().unwrap(),
+                        published_at: None,
+                        submitted_at: "2025-01-09T00:00:00Z".parse().unwrap(),
+                        is_minimized: false,
+                        comments: GitHubPRCommentConnection {
+                            total_count: 0,
+                            nodes: vec![],
+                        },
+                    })
+                    .collect(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn compliance_by_owner_approval() {
+        let schema = TestSchema::with_teams(vec![Team {
+            slug: "backend".to_string(),
+            members: vec!["bob".to_string()],
+        }]);
+        schema
+            .db
+            .insert_codeowners(
+                vec![
+                    rule("*", &["@alice"]),
+                    rule("/src/", &["@aicers/backend"]),
+                    rule("/src/vendor/", &[]),
+                ],
+                "aicers",
+                "github-dashboard-server",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_pull_requests(
+                vec![
+                    merged_pr(1, &["README.md", "src/main.rs"], &["alice", "bob"]),
+                    merged_pr(2, &["src/main.rs"], &["alice"]),
+                    merged_pr(3, &["src/vendor/lib.rs"], &[]),
+                    merged_pr(4, &["README.md"], &["Alice"]),
+                ],
+                "aicers",
+                "github-dashboard-server",
+            )
+            .unwrap();
+        // A repository without a `CODEOWNERS` file.
+        let mut other = merged_pr(1, &["README.md"], &[]);
+        other.repository.name = "other".to_string();
+        schema
+            .db
+            .insert_pull_requests(vec![other], "aicers", "other")
+            .unwrap();
+
+        let query = r"
+        {
+            codeownerCompliance(filter: {}) {
+                repositories {
+                    repo
+                    ownedPrCount
+                    compliantPrCount
+                    complianceRate
+                }
+                violations {
+                    number
+                    paths {
+                        path
+                        owners
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["codeownerCompliance"],
+            serde_json::json!({
+                "repositories": [{
+                    "repo": "github-dashboard-server",
+                    "ownedPrCount": 3,
+                    "compliantPrCount": 2,
+                    "complianceRate": 2.0 / 3.0,
+                }],
+                "violations": [{
+                    "number": 2,
+                    "paths": [{ "path": "src/main.rs", "owners": ["@aicers/backend"] }],
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use anyhow::{anyhow, Context as AnyhowContext};
+use async_graphql::{
+    connection::{query, Connection, EmptyFields},
+    Context, Enum, InputObject, Object, Result, SimpleObject,
+};
+
+use crate::{
+    api::{
+        self,
+        commit_convention_stat::{parse_convention, CommitConvention},
+        DateTimeUtc,
+    },
+    database::{
+        self,
+        commit::GitCommit,
+        commit_link::{parse_commit_links, CommitLinkKind as CommitLinkKindDbSchema},
+        Database, TryFromKeyValue,
+    },
+};
+
+#[derive(SimpleObject)]
+pub(crate) struct Commit {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) oid: String,
+    pub(crate) author_name: String,
+    pub(crate) author_email: String,
+    pub(crate) authored_at: DateTimeUtc,
+    pub(crate) committer_name: String,
+    pub(crate) committer_email: String,
+    pub(crate) committed_at: DateTimeUtc,
+    pub(crate) message: String,
+    pub(crate) parent_count: usize,
+    /// The changes against the first parent. Empty for the oldest commit of a
+    /// shallow clone, as its parents were not fetched.
+    pub(crate) files: Vec<FileChange>,
+    /// The issues and pull requests the message refers to.
+    pub(crate) links: Vec<CommitLink>,
+    /// The type and scope of the message, or `None` if the message does not
+    /// follow the Conventional Commits specification.
+    pub(crate) convention: Option<CommitConvention>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct FileChange {
+    pub(crate) path: String,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CommitLink {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    /// The number of the issue or pull request.
+    pub(crate) number: i32,
+    pub(crate) kind: CommitLinkKind,
+}
+
+/// How a commit message refers to an issue or a pull request.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum CommitLinkKind {
+    /// The message mentions `#N` or `owner/repo#N`.
+    Reference,
+    /// The message closes the issue with a keyword such as `Fixes #N`.
+    Closes,
+    /// The commit merged the pull request.
+    Merge,
+}
+
+impl From<CommitLinkKindDbSchema> for CommitLinkKind {
+    fn from(kind: CommitLinkKindDbSchema) -> Self {
+        match kind {
+            CommitLinkKindDbSchema::Reference => Self::Reference,
+            CommitLinkKindDbSchema::Closes => Self::Closes,
+            CommitLinkKindDbSchema::Merge => Self::Merge,
+        }
+    }
+}
+
+/// A commit linked to an issue or a pull request.
+#[derive(SimpleObject)]
+pub(crate) struct LinkedCommit {
+    pub(crate) kind: CommitLinkKind,
+    pub(crate) commit: Commit,
+}
+
+/// Returns the stored commits linked to the issue or pull request with the
+/// given number.
+pub(super) fn linked_commits(
+    db: &Database,
+    owner: &str,
+    name: &str,
+    number: i32,
+) -> anyhow::Result<Vec<LinkedCommit>> {
+    let mut commits = Vec::new();
+    for link in db.linked_commits(owner, name, number)? {
+        if let Some(commit) = db.commit(&link.owner, &link.repo, &link.oid)? {
+            commits.push(LinkedCommit {
+                kind: link.kind.into(),
+                commit,
+            });
+        }
+    }
+    Ok(commits)
+}
+
+impl TryFromKeyValue for Commit {
+    fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
+        let (owner, repo) = std::str::from_utf8(key)
+            .ok()
+            .and_then(|key| key.rsplit_once('/'))
+            .and_then(|(repository, _)| repository.split_once('/'))
+            .ok_or_else(|| anyhow!("invalid key in database: {key:02x?}"))?;
+        let commit: GitCommit = database::decode(value)
+            .with_context(|| format!("Deserialization failed for value: {value:?}"))?;
+        let links = parse_commit_links(&commit.message, owner, repo)
+            .into_iter()
+            .map(|link| CommitLink {
+                owner: link.owner,
+                repo: link.repo,
+                number: link.number,
+                kind: link.kind.into(),
+            })
+            .collect();
+        let convention = parse_convention(&commit.message);
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            oid: commit.oid,
+            author_name: commit.author_name,
+            author_email: commit.author_email,
+            authored_at: DateTimeUtc(commit.authored_at),
+            committer_name: commit.committer_name,
+            committer_email: commit.committer_email,
+            committed_at: DateTimeUtc(commit.committed_at),
+            message: commit.message,
+            parent_count: commit.parent_count,
+            files: commit
+                .files
+                .into_iter()
+                .map(|file| FileChange {
+                    path: file.path,
+                    additions: file.additions,
+                    deletions: file.deletions,
+                })
+                .collect(),
+            links,
+            convention,
+        })
+    }
+}
+
+impl api::CursorKey for Commit {
+    /// Returns the commit time as eight big-endian bytes with the sign bit
+    /// flipped, followed by the key of the commit, so that cursors sort in
+    /// commit time order.
+    fn cursor_key(&self) -> Vec<u8> {
+        let mut key = self.committed_at.0.as_second().to_be_bytes().to_vec();
+        key[0] ^= 0x80;
+        key.extend_from_slice(format!("{}/{}/{}", self.owner, self.repo, self.oid).as_bytes());
+        key
+    }
+}
+
+#[derive(InputObject, Debug, Default)]
+pub(crate) struct CommitFilter {
+    /// Filter by repository owner.
+    owner: Option<String>,
+    /// Filter by repository name.
+    repo: Option<String>,
+}
+
+impl CommitFilter {
+    fn matches(&self, commit: &Commit) -> bool {
+        self.owner
+            .as_ref()
+            .is_none_or(|owner| commit.owner == *owner)
+            && self.repo.as_ref().is_none_or(|repo| commit.repo == *repo)
+    }
+}
+
+/// Returns the commits satisfying the filter whose cursors are within
+/// `[start, end)`, ordered by commit time.
+fn commits_by_time(
+    db: &Database,
+    filter: &CommitFilter,
+    start: Option<&[u8]>,
+    end: Option<&[u8]>,
+) -> std::vec::IntoIter<anyhow::Result<Commit>> {
+    let commits = match (&filter.owner, &filter.repo) {
+        (Some(owner), Some(repo)) => db.repository_commits(owner, repo),
+        _ => db.commits(None, None),
+    };
+    let commits = commits
+        .filter_map(|commit| {
+            commit
+                .map(|commit| filter.matches(&commit).then_some(commit))
+                .transpose()
+        })
+        .collect::<anyhow::Result<Vec<_>>>();
+    let commits = match commits {
+        Ok(commits) => commits,
+        Err(error) => return vec![Err(error)].into_iter(),
+    };
+    let mut keyed: Vec<_> = commits
+        .into_iter()
+        .map(|commit| (api::CursorKey::cursor_key(&commit), commit))
+        .filter(|(key, _)| {
+            start.is_none_or(|start| key.as_slice() >= start)
+                && end.is_none_or(|end| key.as_slice() < end)
+        })
+        .collect();
+    keyed.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    keyed
+        .into_iter()
+        .map(|(_, commit)| Ok(commit))
+        .collect::<Vec<_>>()
+        .into_iter()
+}
+
+#[derive(Default)]
+pub(super) struct CommitQuery;
+
+#[Object]
+impl CommitQuery {
+    /// Returns the commits read from the local clones, oldest first by commit
+    /// time.
+    async fn commits(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] filter: CommitFilter,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<String, Commit, EmptyFields, EmptyFields>> {
+        query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                api::load_connection(
+                    ctx,
+                    |db, start, end| commits_by_time(db, &filter, start, end),
+                    after,
+                    before,
+                    first,
+                    last,
+                )
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::{
+            commit::{GitCommit, GitFileChange},
+            commit_link::parse_commit_links,
+        },
+    };
+
+    #[tokio::test]
+    async fn commits_first() {
+        let schema = TestSchema::new();
+        let commits = vec![
+            GitCommit {
+                oid: "1111".to_string(),
+                author_name: "Alice".to_string(),
+                message: "Add README".to_string(),
+                files: vec![GitFileChange {
+                    path: "README.md".to_string(),
+                    additions: 10,
+                    deletions: 0,
+                }],
+                ..Default::default()
+            },
+            GitCommit {
+                oid: "2222".to_string(),
+                parent_count: 1,
+                ..Default::default()
+            },
+        ];
+        schema
+            .db
+            .insert_commits(commits, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r"
+        {
+            commits(first: 1) {
+                edges {
+                    node {
+                        owner
+                        repo
+                        oid
+                        authorName
+                        files {
+                            path
+                            additions
+                        }
+                    }
+                }
+                pageInfo {
+                    hasNextPage
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commits"],
+            serde_json::json!({
+                "edges": [{
+                    "node": {
+                        "owner": "aicers",
+                        "repo": "github-dashboard-server",
+                        "oid": "1111",
+                        "authorName": "Alice",
+                        "files": [{ "path": "README.md", "additions": 10 }]
+                    }
+                }],
+                "pageInfo": { "hasNextPage": true }
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn commits_of_repository_by_time() {
+        let schema = TestSchema::new();
+        let commit = |oid: &str, committed_at: &str| GitCommit {
+            oid: oid.to_string(),
+            committed_at: committed_at.parse().unwrap(),
+            ..Default::default()
+        };
+        schema
+            .db
+            .insert_commits(
+                vec![
+                    commit("1111", "2025-01-03T00:00:00Z"),
+                    commit("2222", "2025-01-01T00:00:00Z"),
+                    commit("3333", "2025-01-02T00:00:00Z"),
+                ],
+                "aicers",
+                "frontary",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_commits(
+                vec![commit("4444", "2025-01-01T12:00:00Z")],
+                "aicers",
+                "review",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            commits(filter: {owner: "aicers", repo: "frontary"}, first: 2) {
+                edges {
+                    node {
+                        oid
+                    }
+                }
+                pageInfo {
+                    hasNextPage
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commits"],
+            serde_json::json!({
+                "edges": [
+                    { "node": { "oid": "2222" } },
+                    { "node": { "oid": "3333" } }
+                ],
+                "pageInfo": { "hasNextPage": true }
+            })
+        );
+
+        let query = r"
+        {
+            commits(last: 2) {
+                edges {
+                    node {
+                        oid
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commits"]["edges"],
+            serde_json::json!([
+                { "node": { "oid": "3333" } },
+                { "node": { "oid": "1111" } }
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn commit_links() {
+        let schema = TestSchema::new();
+        let message = "Fix crash on startup (#12)\n\nFixes #3. See aicers/review#4 and #3.";
+        let commit = GitCommit {
+            oid: "1111".to_string(),
+            message: message.to_string(),
+            ..Default::default()
+        };
+        schema
+            .db
+            .insert_commit_links(
+                parse_commit_links(message, "aicers", "frontary"),
+                "aicers",
+                "frontary",
+                "1111",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_commits(vec![commit], "aicers", "frontary")
+            .unwrap();
+
+        let query = r"
+        {
+            commits {
+                edges {
+                    node {
+                        links {
+                            repo
+                            number
+                            kind
+                        }
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commits"]["edges"][0]["node"]["links"],
+            serde_json::json!([
+                { "repo": "frontary", "number": 3, "kind": "CLOSES" },
+                { "repo": "frontary", "number": 12, "kind": "MERGE" },
+                { "repo": "review", "number": 4, "kind": "REFERENCE" },
+            ])
+        );
+        let links = schema.db.linked_commits("aicers", "frontary", 12).unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].oid, "1111");
+        assert!(schema
+            .db
+            .linked_commits("aicers", "frontary", 1)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+use std::{cmp::Reverse, collections::BTreeMap, sync::LazyLock};
+
+use anyhow::Context as AnyhowContext;
+use async_graphql::{Context, Object, Result, SimpleObject};
+use num_traits::ToPrimitive;
+use regex::Regex;
+
+use crate::{api::commit_stat::CommitStatFilter, Database};
+
+static CONVENTIONAL_SUBJECT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<type>[A-Za-z]+)(?:\((?P<scope>[^()\s][^()]*)\))?(?P<breaking>!)?: \S")
+        .expect("valid regex")
+});
+static BREAKING_CHANGE_FOOTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^BREAKING[ -]CHANGE: \S").expect("valid regex"));
+
+/// The type and scope of a commit message following the Conventional Commits
+/// specification.
+#[derive(SimpleObject, Debug, PartialEq)]
+pub(crate) struct CommitConvention {
+    /// The type of the change, in lowercase, such as `feat` or `fix`.
+    #[graphql(name = "type")]
+    pub(crate) commit_type: String,
+    pub(crate) scope: Option<String>,
+    /// Whether the subject has a `!` after the type or scope, or the message
+    /// has a `BREAKING CHANGE:` footer.
+    pub(crate) breaking: bool,
+}
+
+/// Parses a commit message as `type(scope)!: description`, or returns `None`
+/// if the subject does not follow the convention.
+pub(super) fn parse_convention(message: &str) -> Option<CommitConvention> {
+    let subject = message.lines().next().unwrap_or_default();
+    let captures = CONVENTIONAL_SUBJECT.captures(subject)?;
+    Some(CommitConvention {
+        commit_type: captures.name("type")?.as_str().to_lowercase(),
+        scope: captures
+            .name("scope")
+            .map(|scope| scope.as_str().trim().to_string()),
+        breaking: captures.name("breaking").is_some() || BREAKING_CHANGE_FOOTER.is_match(message),
+    })
+}
+
+#[derive(SimpleObject)]
+struct CommitTypeCount {
+    #[graphql(name = "type")]
+    commit_type: String,
+    count: usize,
+}
+
+#[derive(SimpleObject)]
+struct ConventionStat {
+    /// The number of commits, excluding merge commits.
+    commit_count: usize,
+    /// The number of commits following the convention.
+    conventional_count: usize,
+    /// `conventionalCount` divided by `commitCount`, or `None` if there is no
+    /// commit.
+    compliance_rate: Option<f64>,
+    /// The number of conventional commits marked as breaking changes.
+    breaking_change_count: usize,
+    /// The number of conventional commits per type, ordered by count in
+    /// descending order and then by type.
+    types: Vec<CommitTypeCount>,
+}
+
+#[derive(SimpleObject)]
+struct RepositoryConventionStat {
+    owner: String,
+    repo: String,
+    stat: ConventionStat,
+}
+
+#[derive(SimpleObject)]
+struct AuthorConventionStat {
+    /// The name of the commit author.
+    author: String,
+    stat: ConventionStat,
+}
+
+#[derive(SimpleObject)]
+struct CommitConventionStat {
+    total: ConventionStat,
+    /// The statistics per repository, ordered by owner and name.
+    repositories: Vec<RepositoryConventionStat>,
+    /// The statistics per author, ordered by name.
+    authors: Vec<AuthorConventionStat>,
+}
+
+#[derive(Default)]
+struct Tally {
+    commit_count: usize,
+    conventional_count: usize,
+    breaking_change_count: usize,
+    types: BTreeMap<String, usize>,
+}
+
+impl Tally {
+    fn add(&mut self, convention: Option<&CommitConvention>) {
+        self.commit_count += 1;
+        if let Some(convention) = convention {
+            self.conventional_count += 1;
+            if convention.breaking {
+                self.breaking_change_count += 1;
+            }
+            *self
+                .types
+                .entry(convention.commit_type.clone())
+                .or_default() += 1;
+        }
+    }
+
+    fn into_stat(self) -> anyhow::Result<ConventionStat> {
+        let compliance_rate = if self.commit_count == 0 {
+            None
+        } else {
+            Some(
+                self.conventional_count
+                    .to_f64()
+                    .context("Failed to convert usize to f64")?
+                    / self
+                        .commit_count
+                        .to_f64()
+                        .context("Failed to convert usize to f64")?,
+            )
+        };
+        let mut types: Vec<_> = self
+            .types
+            .into_iter()
+            .map(|(commit_type, count)| CommitTypeCount { commit_type, count })
+            .collect();
+        types.sort_by_key(|count| Reverse(count.count));
+        Ok(ConventionStat {
+            commit_count: self.commit_count,
+            conventional_count: self.conventional_count,
+            compliance_rate,
+            breaking_change_count: self.breaking_change_count,
+            types,
+        })
+    }
+}
+
+#[derive(Default)]
+pub(super) struct CommitConventionStatQuery;
+
+#[Object]
+impl CommitConventionStatQuery {
+    /// Returns how many commits of the local clones follow the Conventional
+    /// Commits specification, per repository and per author. Merge commits
+    /// are not counted.
+    #[allow(clippy::unused_async)]
+    async fn commit_convention_stat(
+        &self,
+        ctx: &Context<'_>,
+        filter: CommitStatFilter,
+    ) -> Result<CommitConventionStat> {
+        let db = ctx.data::<Database>()?;
+        let mut total = Tally::default();
+        let mut repositories: BTreeMap<(String, String), Tally> = BTreeMap::new();
+        let mut authors: BTreeMap<String, Tally> = BTreeMap::new();
+        for commit in filter.filter_commits(db.commits(None, None))? {
+            if commit.parent_count > 1 {
+                continue;
+            }
+            let convention = commit.convention.as_ref();
+            total.add(convention);
+            repositories
+                .entry((commit.owner.clone(), commit.repo.clone()))
+                .or_default()
+                .add(convention);
+            authors
+                .entry(commit.author_name.clone())
+                .or_default()
+                .add(convention);
+        }
+
+        Ok(CommitConventionStat {
+            total: total.into_stat()?,
+            repositories: repositories
+                .into_iter()
+                .map(|((owner, repo), tally)| {
+                    Ok(RepositoryConventionStat {
+                        owner,
+                        repo,
+                        stat: tally.into_stat()?,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+            authors: authors
+                .into_iter()
+                .map(|(author, tally)| {
+                    Ok(AuthorConventionStat {
+                        author,
+                        stat: tally.into_stat()?,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_convention, CommitConvention};
+    use crate::{api::TestSchema, database::commit::GitCommit};
+
+    #[test]
+    fn parse_conventional_subjects() {
+        assert_eq!(
+            parse_convention("feat(api)!: remove `commitStat`"),
+            Some(CommitConvention {
+                commit_type: "feat".to_string(),
+                scope: Some("api".to_string()),
+                breaking: true,
+            })
+        );
+        assert_eq!(
+            parse_convention("Fix: handle empty lines\n\nBREAKING CHANGE: lines are kept"),
+            Some(CommitConvention {
+                commit_type: "fix".to_string(),
+                scope: None,
+                breaking: true,
+            })
+        );
+        assert_eq!(parse_convention("Update README"), None);
+        assert_eq!(parse_convention("feat:missing space"), None);
+        assert_eq!(parse_convention("feat(): empty scope"), None);
+    }
+
+    fn create_commit(oid: &str, author: &str, message: &str, parent_count: usize) -> GitCommit {
+        GitCommit {
+            oid: oid.to_string(),
+            author_name: author.to_string(),
+            authored_at: "2025-01-06T00:00:00Z".parse().unwrap(),
+            message: message.to_string(),
+            parent_count,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn convention_stat() {
+        let schema = TestSchema::new();
+        schema
+            .db
+            .insert_commits(
+                vec![
+                    create_commit("1", "Alice", "feat: add query", 1),
+                    create_commit("2", "Alice", "fix(db)!: change keys", 1),
+                    create_commit("3", "Bob", "Fix typo", 1),
+                    create_commit("4", "Bob", "feat(api): add field", 1),
+                    create_commit("5", "Bob", "Merge pull request #1 from a/b", 2),
+                ],
+                "aicers",
+                "github-dashboard-server",
+            )
+            .unwrap();
+
+        let query = r"
+        {
+            commitConventionStat(filter: {}) {
+                total {
+                    commitCount
+                    conventionalCount
+                    complianceRate
+                    breakingChangeCount
+                    types {
+                        type
+                        count
+                    }
+                }
+                repositories {
+                    repo
+                    stat {
+                        commitCount
+                    }
+                }
+                authors {
+                    author
+                    stat {
+                        complianceRate
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commitConventionStat"],
+            serde_json::json!({
+                "total": {
+                    "commitCount": 4,
+                    "conventionalCount": 3,
+                    "complianceRate": 0.75,
+                    "breakingChangeCount": 1,
+                    "types": [
+                        { "type": "feat", "count": 2 },
+                        { "type": "fix", "count": 1 },
+                    ],
+                },
+                "repositories": [
+                    { "repo": "github-dashboard-server", "stat": { "commitCount": 4 } },
+                ],
+                "authors": [
+                    { "author": "Alice", "stat": { "complianceRate": 1.0 } },
+                    { "author": "Bob", "stat": { "complianceRate": 0.5 } },
+                ],
+            })
+        );
+    }
+}
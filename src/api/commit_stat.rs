@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+use jiff::{tz::TimeZone, Span, Timestamp};
+
+use crate::{
+    api::{commit::Commit, DateTimeUtc},
+    database::Iter,
+    Database,
+};
+
+#[derive(InputObject, Debug)]
+pub(crate) struct CommitStatFilter {
+    /// Filter by the name or email of the commit author.
+    author: Option<String>,
+    /// Filter by repository name.
+    repo: Option<String>,
+    /// Start of the authored datetime range. (inclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    begin: Option<DateTimeUtc>,
+    /// End of the authored datetime range. (exclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    end: Option<DateTimeUtc>,
+}
+
+impl CommitStatFilter {
+    pub(super) fn filter_commits(&self, commits: Iter<Commit>) -> anyhow::Result<Vec<Commit>> {
+        Ok(commits
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|commit| {
+                self.author.as_ref().is_none_or(|author| {
+                    commit.author_name == *author || commit.author_email == *author
+                }) && self.repo.as_ref().is_none_or(|repo| commit.repo == *repo)
+                    && self
+                        .begin
+                        .as_ref()
+                        .is_none_or(|begin| commit.authored_at >= *begin)
+                    && self
+                        .end
+                        .as_ref()
+                        .is_none_or(|end| commit.authored_at < *end)
+            })
+            .collect())
+    }
+}
+
+#[derive(SimpleObject)]
+struct WeeklyCommitCount {
+    /// The start of the week (Monday, 00:00 UTC).
+    week: DateTimeUtc,
+    /// The name of the commit author.
+    author: String,
+    count: usize,
+}
+
+#[derive(Default)]
+pub(super) struct CommitStatQuery {}
+
+#[derive(SimpleObject)]
+struct CommitStat {
+    /// The number of commits.
+    commit_count: i32,
+    /// The number of commits per author per week, ordered by week and author.
+    weekly_author_commit_counts: Vec<WeeklyCommitCount>,
+}
+
+/// Returns the start of the week (Monday, 00:00 UTC) containing `timestamp`.
+fn week_start(timestamp: Timestamp) -> Result<Timestamp> {
+    let date = timestamp.to_zoned(TimeZone::UTC).date();
+    let monday = date.checked_sub(Span::new().days(date.weekday().to_monday_zero_offset()))?;
+    Ok(monday.to_zoned(TimeZone::UTC)?.timestamp())
+}
+
+#[Object]
+impl CommitStatQuery {
+    #[allow(clippy::unused_async)]
+    async fn commit_stat(&self, ctx: &Context<'_>, filter: CommitStatFilter) -> Result<CommitStat> {
+        let db = ctx.data::<Database>()?;
+        let filtered = filter.filter_commits(db.commits(None, None))?;
+        let commit_count = filtered.len().try_into()?;
+
+        let mut counts = BTreeMap::new();
+        for commit in &filtered {
+            let week = week_start(commit.authored_at.0)?;
+            *counts
+                .entry((week, commit.author_name.clone()))
+                .or_insert(0) += 1;
+        }
+        let weekly_author_commit_counts = counts
+            .into_iter()
+            .map(|((week, author), count)| WeeklyCommitCount {
+                week: DateTimeUtc(week),
+                author,
+                count,
+            })
+            .collect();
+
+        Ok(CommitStat {
+            commit_count,
+            weekly_author_commit_counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::TestSchema, database::commit::GitCommit};
+
+    fn create_commit(oid: &str, author: &str, authored_at: &str) -> GitCommit {
+        GitCommit {
+            oid: oid.to_string(),
+            author_name: author.to_string(),
+            author_email: format!("{}@example.com", author.to_lowercase()),
+            authored_at: authored_at.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn weekly_author_commit_counts() {
+        let schema = TestSchema::new();
+        let commits = vec![
+            // Wednesday and Sunday of the same week.
+            create_commit("a1", "Alice", "2025-01-08T10:00:00Z"),
+            create_commit("a2", "Alice", "2025-01-12T23:00:00Z"),
+            // Monday of the next week.
+            create_commit("a3", "Alice", "2025-01-13T00:00:00Z"),
+            create_commit("b1", "Bob", "2025-01-09T10:00:00Z"),
+            create_commit("b2", "Bob", "2024-12-01T10:00:00Z"),
+        ];
+        schema
+            .db
+            .insert_commits(commits, "aicers", "github-dashboard-server")
+            .unwrap();
+        schema
+            .db
+            .insert_commits(
+                vec![create_commit("c1", "Alice", "2025-01-08T10:00:00Z")],
+                "aicers",
+                "github-dashboard-client",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            commitStat(filter: {repo: "github-dashboard-server", begin: "2025-01-01T00:00:00Z"}) {
+                commitCount
+                weeklyAuthorCommitCounts {
+                    week
+                    author
+                    count
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["commitStat"],
+            serde_json::json!({
+                "commitCount": 4,
+                "weeklyAuthorCommitCounts": [
+                    { "week": "2025-01-06T00:00:00Z", "author": "Alice", "count": 2 },
+                    { "week": "2025-01-06T00:00:00Z", "author": "Bob", "count": 1 },
+                    { "week": "2025-01-13T00:00:00Z", "author": "Alice", "count": 1 }
+                ]
+            })
+        );
+
+        let query = r#"
+        {
+            commitStat(filter: {author: "bob@example.com"}) {
+                commitCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["commitStat"]["commitCount"], 2);
+    }
+}
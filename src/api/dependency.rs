@@ -0,0 +1,271 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+
+use crate::{
+    database::{
+        dependency::{CargoDependency, DependencyKind as DependencyKindDbSchema},
+        Database,
+    },
+    settings::Repository as RepoInfo,
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum DependencyKind {
+    Normal,
+    Dev,
+    Build,
+}
+
+impl From<DependencyKindDbSchema> for DependencyKind {
+    fn from(kind: DependencyKindDbSchema) -> Self {
+        match kind {
+            DependencyKindDbSchema::Normal => Self::Normal,
+            DependencyKindDbSchema::Dev => Self::Dev,
+            DependencyKindDbSchema::Build => Self::Build,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CrateDependency {
+    /// The name of the crate.
+    pub(crate) name: String,
+    /// The manifests depending on the crate, ordered by repository and
+    /// manifest path.
+    pub(crate) usages: Vec<DependencyUsage>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DependencyUsage {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    /// The path of the `Cargo.toml` declaring the dependency.
+    pub(crate) manifest: String,
+    pub(crate) kind: DependencyKind,
+    pub(crate) requirement: Option<String>,
+    /// The version locked in `Cargo.lock`, if the repository has one.
+    pub(crate) resolved_version: Option<String>,
+    pub(crate) features: Vec<String>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CrateDrift {
+    pub(crate) name: String,
+    /// The locked versions of the crate, oldest first.
+    pub(crate) versions: Vec<ResolvedVersion>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct ResolvedVersion {
+    pub(crate) version: String,
+    /// The repositories locking the crate at the version, as `owner/name`.
+    pub(crate) repositories: Vec<String>,
+}
+
+/// Returns the dependencies of the configured repositories, grouped by
+/// crate.
+fn load_dependencies(
+    ctx: &Context<'_>,
+    repo: Option<&str>,
+) -> Result<BTreeMap<String, Vec<DependencyUsage>>> {
+    let db = ctx.data::<Database>()?;
+    let repositories = ctx.data::<Arc<Vec<RepoInfo>>>()?;
+    let mut crates: BTreeMap<String, Vec<DependencyUsage>> = BTreeMap::new();
+    for repo_info in repositories
+        .iter()
+        .filter(|repo_info| repo.is_none_or(|repo| repo_info.name == repo))
+    {
+        let mut dependencies = db.dependencies(&repo_info.owner, &repo_info.name)?;
+        dependencies.sort_by(|a, b| a.manifest.cmp(&b.manifest));
+        for dependency in dependencies {
+            let CargoDependency {
+                manifest,
+                name,
+                kind,
+                requirement,
+                resolved,
+                features,
+            } = dependency;
+            crates.entry(name).or_default().push(DependencyUsage {
+                owner: repo_info.owner.clone(),
+                repo: repo_info.name.clone(),
+                manifest,
+                kind: kind.into(),
+                requirement,
+                resolved_version: resolved,
+                features,
+            });
+        }
+    }
+    Ok(crates)
+}
+
+/// Orders versions by their numeric components, so that `0.10.0` comes after
+/// `0.9.0`.
+fn version_key(version: &str) -> (Vec<u64>, String) {
+    let numbers = version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or_default())
+        .collect();
+    (numbers, version.to_string())
+}
+
+#[derive(Default)]
+pub(super) struct DependencyQuery;
+
+#[Object]
+impl DependencyQuery {
+    /// Returns the crates the `Cargo.toml` files of the configured
+    /// repositories depend on, ordered by name.
+    ///
+    /// `name` limits the result to a crate, and `repo` to a repository.
+    #[allow(clippy::unused_async)]
+    async fn dependencies(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        repo: Option<String>,
+    ) -> Result<Vec<CrateDependency>> {
+        Ok(load_dependencies(ctx, repo.as_deref())?
+            .into_iter()
+            .filter(|(crate_name, _)| name.as_ref().is_none_or(|name| crate_name == name))
+            .map(|(name, usages)| CrateDependency { name, usages })
+            .collect())
+    }
+
+    /// Returns the crates locked at different versions across the configured
+    /// repositories, ordered by name.
+    #[allow(clippy::unused_async)]
+    async fn dependency_drift(&self, ctx: &Context<'_>) -> Result<Vec<CrateDrift>> {
+        Ok(load_dependencies(ctx, None)?
+            .into_iter()
+            .filter_map(|(name, usages)| {
+                let mut versions: BTreeMap<(Vec<u64>, String), BTreeSet<String>> = BTreeMap::new();
+                for usage in usages {
+                    if let Some(version) = usage.resolved_version {
+                        versions
+                            .entry(version_key(&version))
+                            .or_default()
+                            .insert(format!("{}/{}", usage.owner, usage.repo));
+                    }
+                }
+                let repositories: BTreeSet<&String> = versions.values().flatten().collect();
+                if versions.len() < 2 || repositories.len() < 2 {
+                    return None;
+                }
+                let versions = versions
+                    .into_iter()
+                    .map(|((_, version), repositories)| ResolvedVersion {
+                        version,
+                        repositories: repositories.into_iter().collect(),
+                    })
+                    .collect();
+                Some(CrateDrift { name, versions })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::dependency::{CargoDependency, DependencyKind},
+        settings::Repository,
+    };
+
+    fn repository(name: &str) -> Repository {
+        Repository {
+            owner: "aicers".to_string(),
+            name: name.to_string(),
+            branch: None,
+            protocol: None,
+        }
+    }
+
+    fn dependency(name: &str, resolved: &str) -> CargoDependency {
+        CargoDependency {
+            manifest: "Cargo.toml".to_string(),
+            name: name.to_string(),
+            kind: DependencyKind::Normal,
+            requirement: Some(resolved.to_string()),
+            resolved: Some(resolved.to_string()),
+            features: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn dependencies_and_drift() {
+        let schema = TestSchema::with_repositories(vec![
+            repository("giganto"),
+            repository("review"),
+            repository("unused"),
+        ]);
+        schema
+            .db
+            .insert_dependencies(
+                vec![
+                    dependency("tokio", "1.40.0"),
+                    dependency("serde", "1.0.210"),
+                ],
+                "aicers",
+                "giganto",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_dependencies(
+                vec![dependency("tokio", "1.9.0"), dependency("serde", "1.0.210")],
+                "aicers",
+                "review",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            dependencies(name: "tokio") {
+                name
+                usages {
+                    repo
+                    kind
+                    resolvedVersion
+                }
+            }
+            dependencyDrift {
+                name
+                versions {
+                    version
+                    repositories
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["dependencies"],
+            serde_json::json!([{
+                "name": "tokio",
+                "usages": [
+                    { "repo": "giganto", "kind": "NORMAL", "resolvedVersion": "1.40.0" },
+                    { "repo": "review", "kind": "NORMAL", "resolvedVersion": "1.9.0" },
+                ],
+            }])
+        );
+        assert_eq!(
+            data["dependencyDrift"],
+            serde_json::json!([{
+                "name": "tokio",
+                "versions": [
+                    { "version": "1.9.0", "repositories": ["aicers/review"] },
+                    { "version": "1.40.0", "repositories": ["aicers/giganto"] },
+                ],
+            }])
+        );
+    }
+}
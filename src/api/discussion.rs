@@ -1,5 +1,3 @@
-use std::fmt;
-
 use anyhow::Context as AnyhowContext;
 use async_graphql::{
     connection::{query, Connection, EmptyFields},
@@ -83,15 +81,15 @@ impl TryFromKeyValue for Discussion {
     fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
         let (owner, repo, number) = database::parse_key(key)
             .with_context(|| format!("invalid key in database: {key:02x?}"))?;
-        let discussion_schema = bincode::deserialize::<DiscussionDbSchema>(value)?;
+        let discussion_schema = database::decode::<DiscussionDbSchema>(value)?;
         let discussion = Discussion::new(owner, repo, number, discussion_schema);
         Ok(discussion)
     }
 }
 
-impl fmt::Display for Discussion {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+impl api::CursorKey for Discussion {
+    fn cursor_key(&self) -> Vec<u8> {
+        database::record_key(&self.owner, &self.repo, self.number)
     }
 }
 
@@ -1,8 +1,10 @@
+use std::collections::HashSet;
+
 use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
 
 use crate::{
-    api::{discussion::DiscussionComment, DateTimeUtc, Discussion},
-    database::Iter,
+    api::{discussion::DiscussionComment, team, DateTimeUtc, Discussion},
+    database::{index::IndexFilter, Iter},
     Database,
 };
 
@@ -10,6 +12,8 @@ use crate::{
 pub(crate) struct DiscussionStatFilter {
     /// Filter by discussion author.
     author: Option<String>,
+    /// Filter by the team (slug) the discussion author belongs to.
+    team: Option<String>,
     /// Filter by repository name.
     repo: Option<String>,
     /// Start of the creation datetime range. (inclusive)
@@ -21,14 +25,37 @@ pub(crate) struct DiscussionStatFilter {
 }
 
 impl DiscussionStatFilter {
-    fn filter_discussions(&self, discussions: Iter<Discussion>) -> Vec<Discussion> {
-        discussions
+    /// Returns the conditions of the filter that can be looked up in the
+    /// discussion index.
+    fn index_filter<'a>(&'a self, team_members: Option<&'a HashSet<String>>) -> IndexFilter<'a> {
+        IndexFilter {
+            authors: self
+                .author
+                .as_deref()
+                .map(|author| vec![author])
+                .or_else(|| {
+                    team_members.map(|members| members.iter().map(String::as_str).collect())
+                }),
+            repo: self.repo.as_deref(),
+            created_begin: self.begin.as_ref().map(|begin| begin.0),
+            created_end: self.end.as_ref().map(|end| end.0),
+            ..Default::default()
+        }
+    }
+
+    fn filter_discussions(
+        &self,
+        discussions: Iter<Discussion>,
+        team_members: Option<&HashSet<String>>,
+    ) -> anyhow::Result<Vec<Discussion>> {
+        Ok(discussions
+            .collect::<anyhow::Result<Vec<_>>>()?
             .into_iter()
-            .filter_map(std::result::Result::ok)
             .filter(|d| {
                 self.author
                     .as_ref()
                     .is_none_or(|author| d.author == *author)
+                    && team_members.is_none_or(|members| members.contains(&d.author))
                     && self.repo.as_ref().is_none_or(|repo| d.repo == *repo)
                     && self
                         .begin
@@ -36,26 +63,31 @@ impl DiscussionStatFilter {
                         .is_none_or(|begin| d.created_at >= *begin)
                     && self.end.as_ref().is_none_or(|end| d.created_at < *end)
             })
-            .collect()
+            .collect())
     }
 
-    fn filter_comments(&self, discussions: Iter<Discussion>) -> Vec<DiscussionComment> {
-        discussions
+    fn filter_comments(
+        &self,
+        discussions: Iter<Discussion>,
+        team_members: Option<&HashSet<String>>,
+    ) -> anyhow::Result<Vec<DiscussionComment>> {
+        Ok(discussions
+            .collect::<anyhow::Result<Vec<_>>>()?
             .into_iter()
-            .filter_map(std::result::Result::ok)
             .filter(|d| self.repo.as_ref().is_none_or(|repo| d.repo == *repo))
             .flat_map(|d| d.comments)
             .filter(|c| {
                 self.author
                     .as_ref()
                     .is_none_or(|author| c.author == *author)
+                    && team_members.is_none_or(|members| members.contains(&c.author))
                     && self
                         .begin
                         .as_ref()
                         .is_none_or(|begin| c.created_at >= *begin)
                     && self.end.as_ref().is_none_or(|end| c.created_at < *end)
             })
-            .collect()
+            .collect())
     }
 }
 
@@ -79,14 +111,28 @@ impl DiscussionStatQuery {
         filter: DiscussionStatFilter,
     ) -> Result<DiscussionStat> {
         let db = ctx.data::<Database>()?;
+        let team_members = filter
+            .team
+            .as_deref()
+            .map(|slug| team::team_members(ctx, slug))
+            .transpose()?;
 
         let total_count = filter
-            .filter_discussions(db.discussions(None, None))
+            .filter_discussions(
+                db.discussions_matching(&filter.index_filter(team_members.as_ref()))?,
+                team_members.as_ref(),
+            )?
             .len()
             .try_into()?;
 
         let comment_count = filter
-            .filter_comments(db.discussions(None, None))
+            .filter_comments(
+                db.discussions_matching(&IndexFilter {
+                    repo: filter.repo.as_deref(),
+                    ..Default::default()
+                })?,
+                team_members.as_ref(),
+            )?
             .len()
             .try_into()?;
 
@@ -101,7 +147,10 @@ impl DiscussionStatQuery {
 mod tests {
     use jiff::Timestamp;
 
-    use crate::{api::TestSchema, database::DiscussionDbSchema};
+    use crate::{
+        api::TestSchema,
+        database::{team::GitHubTeam, DiscussionDbSchema},
+    };
 
     fn create_discussions(n: usize) -> Vec<DiscussionDbSchema> {
         (0..n)
@@ -155,6 +204,45 @@ mod tests {
         assert_eq!(data["discussionStat"]["totalCount"], 1);
     }
 
+    #[tokio::test]
+    async fn total_and_comment_count_by_team() {
+        let schema = TestSchema::new();
+        let mut discussions = create_discussions(2);
+        discussions[0].author = "alice".to_string();
+        discussions[0].comments.nodes = vec![
+            create_comment("First", "bob", "2025-01-01T00:00:00Z"),
+            create_comment("Second", "carol", "2025-01-02T00:00:00Z"),
+        ];
+        discussions[0].comments.total_count = 2;
+        discussions[1].author = "carol".to_string();
+        schema
+            .db
+            .insert_discussions(discussions, "aicers", "github-dashboard-server")
+            .unwrap();
+        schema
+            .db
+            .insert_teams(
+                vec![GitHubTeam {
+                    slug: "backend".to_string(),
+                    name: "Backend".to_string(),
+                    members: vec!["alice".to_string(), "bob".to_string()],
+                }],
+                "aicers",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            discussionStat(filter: {team: "backend"}) {
+                totalCount
+                commentCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["discussionStat"]["totalCount"], 1);
+        assert_eq!(data["discussionStat"]["commentCount"], 1);
+    }
+
     #[tokio::test]
     async fn total_count_by_begin_end() {
         let schema = TestSchema::new();
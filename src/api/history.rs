@@ -0,0 +1,108 @@
+use async_graphql::{Enum, SimpleObject};
+
+use crate::{
+    api::DateTimeUtc,
+    database::history::{ChangedField as ChangedFieldDbSchema, FieldChange as FieldChangeDbSchema},
+};
+
+/// A field of an issue or a pull request whose changes are recorded.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum ChangedField {
+    Title,
+    State,
+    Labels,
+    Assignees,
+    /// The status of the record in a project.
+    ProjectStatus,
+}
+
+impl From<ChangedFieldDbSchema> for ChangedField {
+    fn from(field: ChangedFieldDbSchema) -> Self {
+        match field {
+            ChangedFieldDbSchema::Title => Self::Title,
+            ChangedFieldDbSchema::State => Self::State,
+            ChangedFieldDbSchema::Labels => Self::Labels,
+            ChangedFieldDbSchema::Assignees => Self::Assignees,
+            ChangedFieldDbSchema::ProjectStatus => Self::ProjectStatus,
+        }
+    }
+}
+
+/// A change of a field, found when a sync fetched a record that differs from
+/// the stored one.
+#[derive(SimpleObject)]
+pub(crate) struct FieldChange {
+    /// When the sync that found the change ran.
+    pub(crate) changed_at: DateTimeUtc,
+    pub(crate) field: ChangedField,
+    /// The title of the project, for a change of `PROJECT_STATUS`.
+    pub(crate) project: Option<String>,
+    /// The previous value, or `null` if an item was added to a list.
+    pub(crate) from: Option<String>,
+    /// The new value, or `null` if an item was removed from a list.
+    pub(crate) to: Option<String>,
+}
+
+impl From<FieldChangeDbSchema> for FieldChange {
+    fn from(change: FieldChangeDbSchema) -> Self {
+        Self {
+            changed_at: DateTimeUtc(change.changed_at),
+            field: change.field.into(),
+            project: change.project,
+            from: change.from,
+            to: change.to,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::TestSchema, database::GitHubIssue, outbound::issues::IssueState};
+
+    fn issue(state: IssueState, label: &str) -> GitHubIssue {
+        GitHubIssue {
+            number: 1,
+            title: "Crash on start".to_string(),
+            state,
+            labels: vec![label.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn issue_history() {
+        let schema = TestSchema::new();
+        schema
+            .db
+            .insert_issues(vec![issue(IssueState::OPEN, "bug")], "aicers", "frontary")
+            .unwrap();
+        schema
+            .db
+            .insert_issues(vec![issue(IssueState::CLOSED, "ui")], "aicers", "frontary")
+            .unwrap();
+
+        let query = r"
+        {
+            issues(first: 1) {
+                edges {
+                    node {
+                        history {
+                            field
+                            from
+                            to
+                        }
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["issues"]["edges"][0]["node"]["history"],
+            serde_json::json!([
+                { "field": "STATE", "from": "OPEN", "to": "CLOSED" },
+                { "field": "LABELS", "from": "bug", "to": null },
+                { "field": "LABELS", "from": null, "to": "ui" },
+            ])
+        );
+    }
+}
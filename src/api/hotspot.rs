@@ -0,0 +1,198 @@
+use std::collections::{HashMap, HashSet};
+
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+use crate::{
+    api::{commit::Commit, DateTimeUtc},
+    database::Database,
+};
+
+/// The number of hotspots returned when `limit` is not given.
+const DEFAULT_HOTSPOT_LIMIT: usize = 20;
+
+#[derive(SimpleObject)]
+pub(crate) struct Hotspot {
+    pub(crate) path: String,
+    /// The number of commits that changed the file.
+    pub(crate) change_count: usize,
+    /// The number of lines added and deleted.
+    pub(crate) churn: usize,
+    /// The number of distinct authors (by email) who changed the file.
+    pub(crate) author_count: usize,
+    pub(crate) last_modified_at: DateTimeUtc,
+}
+
+#[derive(Default)]
+struct PathChurn<'a> {
+    change_count: usize,
+    churn: usize,
+    authors: HashSet<&'a str>,
+    last_modified_at: Option<DateTimeUtc>,
+}
+
+/// Returns the files changed most often, then with the most lines churned.
+///
+/// Merge commits are skipped, since their changes are already counted in the
+/// merged commits.
+fn hotspots(commits: &[Commit], limit: usize) -> Vec<Hotspot> {
+    let mut paths: HashMap<&str, PathChurn> = HashMap::new();
+    for commit in commits.iter().filter(|commit| commit.parent_count <= 1) {
+        for file in &commit.files {
+            let path = paths.entry(&file.path).or_default();
+            path.change_count += 1;
+            path.churn += file.additions + file.deletions;
+            path.authors.insert(&commit.author_email);
+            if path
+                .last_modified_at
+                .is_none_or(|last| last < commit.authored_at)
+            {
+                path.last_modified_at = Some(commit.authored_at);
+            }
+        }
+    }
+    let mut hotspots: Vec<Hotspot> = paths
+        .into_iter()
+        .filter_map(|(path, churn)| {
+            Some(Hotspot {
+                path: path.to_string(),
+                change_count: churn.change_count,
+                churn: churn.churn,
+                author_count: churn.authors.len(),
+                last_modified_at: churn.last_modified_at?,
+            })
+        })
+        .collect();
+    hotspots.sort_by(|a, b| {
+        b.change_count
+            .cmp(&a.change_count)
+            .then(b.churn.cmp(&a.churn))
+            .then(a.path.cmp(&b.path))
+    });
+    hotspots.truncate(limit);
+    hotspots
+}
+
+#[derive(Default)]
+pub(super) struct HotspotQuery;
+
+#[Object]
+impl HotspotQuery {
+    /// Returns the files of the repository that change most often.
+    ///
+    /// Only commits authored at or after `since` are counted. The result is
+    /// computed from the per-file changes stored for each commit, so each sync
+    /// only diffs the new commits.
+    #[allow(clippy::unused_async)]
+    async fn hotspots(
+        &self,
+        ctx: &Context<'_>,
+        owner: String,
+        repo: String,
+        since: Option<DateTimeUtc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Hotspot>> {
+        let db = ctx.data::<Database>()?;
+        let commits: Vec<Commit> = db
+            .repository_commits(&owner, &repo)
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
+            .filter(|commit| since.is_none_or(|since| commit.authored_at >= since))
+            .collect();
+        Ok(hotspots(&commits, limit.unwrap_or(DEFAULT_HOTSPOT_LIMIT)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::commit::{GitCommit, GitFileChange},
+    };
+
+    fn create_commit(
+        oid: &str,
+        author: &str,
+        authored_at: &str,
+        files: &[(&str, usize)],
+    ) -> GitCommit {
+        GitCommit {
+            oid: oid.to_string(),
+            author_email: format!("{author}@example.com"),
+            authored_at: authored_at.parse().unwrap(),
+            parent_count: 1,
+            files: files
+                .iter()
+                .map(|(path, additions)| GitFileChange {
+                    path: (*path).to_string(),
+                    additions: *additions,
+                    deletions: 1,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn hotspots_since_and_limit() {
+        let schema = TestSchema::new();
+        let mut merge = create_commit("m", "carol", "2025-01-05T00:00:00Z", &[("src/lib.rs", 99)]);
+        merge.parent_count = 2;
+        let commits = vec![
+            create_commit(
+                "a",
+                "alice",
+                "2025-01-02T00:00:00Z",
+                &[("src/main.rs", 3), ("README.md", 1)],
+            ),
+            create_commit("b", "bob", "2025-01-03T00:00:00Z", &[("src/main.rs", 5)]),
+            create_commit("c", "alice", "2025-01-04T00:00:00Z", &[("src/lib.rs", 10)]),
+            create_commit(
+                "d",
+                "alice",
+                "2024-12-01T00:00:00Z",
+                &[("README.md", 1), ("src/lib.rs", 1)],
+            ),
+            merge,
+        ];
+        schema
+            .db
+            .insert_commits(commits, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r#"
+        {
+            hotspots(
+                owner: "aicers",
+                repo: "github-dashboard-server",
+                since: "2025-01-01T00:00:00Z",
+                limit: 2
+            ) {
+                path
+                changeCount
+                churn
+                authorCount
+                lastModifiedAt
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["hotspots"],
+            serde_json::json!([
+                {
+                    "path": "src/main.rs",
+                    "changeCount": 2,
+                    "churn": 10,
+                    "authorCount": 2,
+                    "lastModifiedAt": "2025-01-03T00:00:00Z"
+                },
+                {
+                    "path": "src/lib.rs",
+                    "changeCount": 1,
+                    "churn": 11,
+                    "authorCount": 1,
+                    "lastModifiedAt": "2025-01-04T00:00:00Z"
+                }
+            ])
+        );
+    }
+}
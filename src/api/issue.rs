@@ -1,22 +1,27 @@
-use std::fmt;
-
 use anyhow::Context as AnyhowContext;
 use async_graphql::{
     connection::{query, Connection, EmptyFields},
-    scalar, Context, Object, Result, SimpleObject,
+    scalar, ComplexObject, Context, Object, Result, SimpleObject,
 };
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    api::{self, DateTimeUtc, TODO_LIST_PROJECT_TITLE, TODO_LIST_STATUS_DONE},
-    database::{self, Database, GitHubIssue, TryFromKeyValue},
+    api::{
+        self, commit::LinkedCommit, history::FieldChange, DateTimeUtc, TODO_LIST_PROJECT_TITLE,
+        TODO_LIST_STATUS_DONE,
+    },
+    database::{self, reaction::GitHubReactionGroup, Database, GitHubIssue, TryFromKeyValue},
     outbound::issues::{IssueState, PullRequestState},
 };
 
 scalar!(IssueState);
 scalar!(PullRequestState);
 
+/// The reaction content GitHub uses for an upvote.
+const REACTION_THUMBS_UP: &str = "THUMBS_UP";
+
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Issue {
     pub(crate) id: String,
     pub(crate) owner: String,
@@ -34,6 +39,7 @@ pub(crate) struct Issue {
     pub(crate) parent: Option<ParentIssue>,
     pub(crate) url: String,
     pub(crate) closed_by_pull_requests: Vec<PullRequestRef>,
+    pub(crate) reactions: Vec<ReactionGroup>,
     pub(crate) created_at: DateTimeUtc,
     pub(crate) updated_at: DateTimeUtc,
     pub(crate) closed_at: Option<DateTimeUtc>,
@@ -54,6 +60,24 @@ pub(crate) struct Comment {
     pub(crate) updated_at: DateTimeUtc,
     pub(crate) repository_name: String,
     pub(crate) url: String,
+    pub(crate) reactions: Vec<ReactionGroup>,
+}
+
+#[derive(SimpleObject, Debug)]
+pub(crate) struct ReactionGroup {
+    pub(crate) content: String,
+    pub(crate) count: i32,
+    pub(crate) users: Vec<String>,
+}
+
+impl From<GitHubReactionGroup> for ReactionGroup {
+    fn from(group: GitHubReactionGroup) -> Self {
+        Self {
+            content: group.content,
+            count: group.count,
+            users: group.users,
+        }
+    }
 }
 
 #[derive(SimpleObject, Debug, Default)]
@@ -112,6 +136,15 @@ pub(crate) struct PullRequestRef {
 }
 
 impl Issue {
+    /// Returns the number of 👍 reactions on the issue.
+    pub(super) fn upvote_count(&self) -> i32 {
+        self.reactions
+            .iter()
+            .filter(|group| group.content == REACTION_THUMBS_UP)
+            .map(|group| group.count)
+            .sum()
+    }
+
     /// We define an issue is "Resolved" if and only if
     /// - Status of the issue is "Closed" AND
     /// - The issue has "to-do list" as a project item and its status is "Done"
@@ -137,11 +170,49 @@ impl Issue {
     }
 }
 
+#[ComplexObject]
+impl Issue {
+    /// The commits whose messages refer to the issue.
+    #[allow(clippy::unused_async)]
+    async fn commits(&self, ctx: &Context<'_>) -> Result<Vec<LinkedCommit>> {
+        let db = ctx.data::<Database>()?;
+        Ok(api::commit::linked_commits(
+            db,
+            &self.owner,
+            &self.repo,
+            self.number,
+        )?)
+    }
+
+    /// Whether the issue was closed with neither a pull request closing it
+    /// nor a commit referring to it.
+    #[allow(clippy::unused_async)]
+    async fn closed_without_link(&self, ctx: &Context<'_>) -> Result<bool> {
+        if self.state != IssueState::CLOSED || !self.closed_by_pull_requests.is_empty() {
+            return Ok(false);
+        }
+        let db = ctx.data::<Database>()?;
+        Ok(api::commit::linked_commits(db, &self.owner, &self.repo, self.number)?.is_empty())
+    }
+
+    /// The changes of the title, state, labels, assignees and project
+    /// statuses found by the syncs, oldest first.
+    #[allow(clippy::unused_async)]
+    async fn history(&self, ctx: &Context<'_>) -> Result<Vec<FieldChange>> {
+        let db = ctx.data::<Database>()?;
+        Ok(db
+            .issue_history(&self.owner, &self.repo, self.number)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
 impl TryFromKeyValue for Issue {
     fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
         let (owner, repo, number) = database::parse_key(key)
             .with_context(|| format!("invalid key in database: {key:02x?}"))?;
-        let issue: GitHubIssue = bincode::deserialize(value)?;
+        let issue: GitHubIssue = database::decode(value)?;
         Ok(Issue {
             id: issue.id,
             owner,
@@ -165,6 +236,7 @@ impl TryFromKeyValue for Issue {
                         body: comment.body,
                         repository_name: comment.repository_name,
                         url: comment.url,
+                        reactions: reaction_groups(comment.reactions),
                         created_at: DateTimeUtc(comment.created_at),
                         updated_at: DateTimeUtc(comment.updated_at),
                     })
@@ -226,6 +298,7 @@ impl TryFromKeyValue for Issue {
                     closed_at: pr.closed_at.map(DateTimeUtc),
                 })
                 .collect(),
+            reactions: reaction_groups(issue.reactions),
             created_at: DateTimeUtc(issue.created_at),
             updated_at: DateTimeUtc(issue.updated_at),
             closed_at: issue.closed_at.map(DateTimeUtc),
@@ -233,9 +306,13 @@ impl TryFromKeyValue for Issue {
     }
 }
 
-impl fmt::Display for Issue {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+fn reaction_groups(groups: Vec<GitHubReactionGroup>) -> Vec<ReactionGroup> {
+    groups.into_iter().map(ReactionGroup::from).collect()
+}
+
+impl api::CursorKey for Issue {
+    fn cursor_key(&self) -> Vec<u8> {
+        database::record_key(&self.owner, &self.repo, self.number)
     }
 }
 
@@ -267,7 +344,16 @@ impl IssueQuery {
 
 #[cfg(test)]
 mod tests {
-    use crate::{api::TestSchema, database::GitHubIssue};
+    use jiff::Timestamp;
+
+    use crate::{
+        api::TestSchema,
+        database::{
+            commit::GitCommit, commit_link::parse_commit_links, issue::GitHubPullRequestRef,
+            GitHubIssue,
+        },
+        outbound::issues::{IssueState, PullRequestState},
+    };
 
     fn create_issues(n: usize) -> Vec<GitHubIssue> {
         (1..=n)
@@ -374,4 +460,88 @@ mod tests {
             "{issues: {pageInfo: {hasPreviousPage: false}}}"
         );
     }
+
+    #[tokio::test]
+    async fn issues_closed_without_link() {
+        let schema = TestSchema::new();
+        let mut issues = create_issues(3);
+        for issue in &mut issues {
+            issue.state = IssueState::CLOSED;
+        }
+        issues[1].closed_by_pull_requests = vec![GitHubPullRequestRef {
+            number: 10,
+            state: PullRequestState::MERGED,
+            author: "alice".to_string(),
+            created_at: Timestamp::default(),
+            updated_at: Timestamp::default(),
+            closed_at: None,
+            url: String::new(),
+        }];
+        schema
+            .db
+            .insert_issues(issues, "aicers", "frontary")
+            .unwrap();
+        schema
+            .db
+            .insert_commits(
+                vec![GitCommit {
+                    oid: "1111".to_string(),
+                    message: "Fix the login form\n\nCloses #3".to_string(),
+                    ..Default::default()
+                }],
+                "aicers",
+                "frontary",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_commit_links(
+                parse_commit_links("Fix the login form\n\nCloses #3", "aicers", "frontary"),
+                "aicers",
+                "frontary",
+                "1111",
+            )
+            .unwrap();
+        // A link whose commit is no longer stored does not count.
+        schema
+            .db
+            .insert_commit_links(
+                parse_commit_links("Closes #1", "aicers", "frontary"),
+                "aicers",
+                "frontary",
+                "2222",
+            )
+            .unwrap();
+
+        let query = r"
+        {
+            issues {
+                edges {
+                    node {
+                        number
+                        closedWithoutLink
+                        commits {
+                            kind
+                            commit {
+                                oid
+                            }
+                        }
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["issues"]["edges"],
+            serde_json::json!([
+                { "node": { "number": 1, "closedWithoutLink": true, "commits": [] } },
+                { "node": { "number": 2, "closedWithoutLink": false, "commits": [] } },
+                { "node": {
+                    "number": 3,
+                    "closedWithoutLink": false,
+                    "commits": [{ "kind": "CLOSES", "commit": { "oid": "1111" } }],
+                } },
+            ])
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use std::collections::BTreeMap;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashSet},
+};
 
 use anyhow::Context as _;
 use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
@@ -6,8 +9,8 @@ use jiff::{SpanTotal, Unit};
 use num_traits::ToPrimitive;
 
 use crate::{
-    api::{issue::Issue, DateTimeUtc, TODO_LIST_PROJECT_TITLE},
-    database::Iter,
+    api::{issue::Issue, team, DateTimeUtc, TODO_LIST_PROJECT_TITLE},
+    database::{index::IndexFilter, Iter},
     outbound::issues::IssueState,
     Database,
 };
@@ -64,12 +67,62 @@ struct IssuePriorityCount {
     count: usize,
 }
 
+/// The maximum number of issues listed in `IssueEngagement::most_upvoted_open_issues`.
+const MOST_UPVOTED_ISSUE_COUNT: usize = 10;
+
+#[derive(SimpleObject)]
+struct UpvotedIssue {
+    owner: String,
+    repo: String,
+    number: i32,
+    title: String,
+    url: String,
+    /// The number of 👍 reactions on the issue.
+    upvote_count: i32,
+}
+
+#[derive(SimpleObject)]
+struct IssueEngagement {
+    /// The open issues with the most 👍 reactions, in descending order.
+    most_upvoted_open_issues: Vec<UpvotedIssue>,
+}
+
+impl IssueEngagement {
+    fn new(issues: &[Issue]) -> Self {
+        let mut upvoted_open_issues: Vec<_> = issues
+            .iter()
+            .filter(|issue| matches!(issue.state, IssueState::OPEN))
+            .map(|issue| (issue, issue.upvote_count()))
+            .filter(|(_, upvote_count)| *upvote_count > 0)
+            .collect();
+        upvoted_open_issues.sort_by_key(|(_, upvote_count)| Reverse(*upvote_count));
+        let most_upvoted_open_issues = upvoted_open_issues
+            .into_iter()
+            .take(MOST_UPVOTED_ISSUE_COUNT)
+            .map(|(issue, upvote_count)| UpvotedIssue {
+                owner: issue.owner.clone(),
+                repo: issue.repo.clone(),
+                number: issue.number,
+                title: issue.title.clone(),
+                url: issue.url.clone(),
+                upvote_count,
+            })
+            .collect();
+
+        Self {
+            most_upvoted_open_issues,
+        }
+    }
+}
+
 #[derive(InputObject, Debug)]
 pub(crate) struct IssueStatFilter {
     /// Filter by assignee.
     assignee: Option<String>,
     /// Filter by issue author.
     author: Option<String>,
+    /// Filter by the team (slug) the issue author belongs to.
+    team: Option<String>,
     /// Filter by repository name.
     repo: Option<String>,
     /// Start of the creation datetime range. (inclusive)
@@ -81,14 +134,38 @@ pub(crate) struct IssueStatFilter {
 }
 
 impl IssueStatFilter {
-    fn filter_issues(&self, issues: Iter<Issue>) -> Vec<Issue> {
-        issues
+    /// Returns the conditions of the filter that can be looked up in the
+    /// issue index.
+    fn index_filter<'a>(&'a self, team_members: Option<&'a HashSet<String>>) -> IndexFilter<'a> {
+        IndexFilter {
+            authors: self
+                .author
+                .as_deref()
+                .map(|author| vec![author])
+                .or_else(|| {
+                    team_members.map(|members| members.iter().map(String::as_str).collect())
+                }),
+            assignee: self.assignee.as_deref(),
+            repo: self.repo.as_deref(),
+            created_begin: self.begin.as_ref().map(|begin| begin.0),
+            created_end: self.end.as_ref().map(|end| end.0),
+            ..Default::default()
+        }
+    }
+
+    fn filter_issues(
+        &self,
+        issues: Iter<Issue>,
+        team_members: Option<&HashSet<String>>,
+    ) -> anyhow::Result<Vec<Issue>> {
+        Ok(issues
+            .collect::<anyhow::Result<Vec<_>>>()?
             .into_iter()
-            .filter_map(std::result::Result::ok)
             .filter(|issue| {
                 self.author
                     .as_ref()
                     .is_none_or(|author| issue.author == *author)
+                    && team_members.is_none_or(|members| members.contains(&issue.author))
                     && self.repo.as_ref().is_none_or(|repo| issue.repo == *repo)
                     && self
                         .begin
@@ -100,7 +177,7 @@ impl IssueStatFilter {
                         .as_ref()
                         .is_none_or(|assignee| issue.assignees.iter().any(|a| a == assignee))
             })
-            .collect()
+            .collect())
     }
 }
 
@@ -123,6 +200,9 @@ struct IssueStat {
 
     /// The distribution of priorities for resolved issues.
     resolved_issue_priority_distribution: Vec<IssuePriorityCount>,
+
+    /// Reaction-based engagement of the issues.
+    engagement: IssueEngagement,
 }
 
 #[Object]
@@ -130,8 +210,13 @@ impl IssueStatQuery {
     #[allow(clippy::unused_async)]
     async fn issue_stat(&self, ctx: &Context<'_>, filter: IssueStatFilter) -> Result<IssueStat> {
         let db = ctx.data::<Database>()?;
-        let issues = db.issues(None, None);
-        let filtered = filter.filter_issues(issues);
+        let team_members = filter
+            .team
+            .as_deref()
+            .map(|slug| team::team_members(ctx, slug))
+            .transpose()?;
+        let issues = db.issues_matching(&filter.index_filter(team_members.as_ref()))?;
+        let filtered = filter.filter_issues(issues, team_members.as_ref())?;
         let open_issue_count = filtered
             .iter()
             .filter(|issue| matches!(issue.state, IssueState::OPEN))
@@ -224,6 +309,7 @@ impl IssueStatQuery {
             resolved_issue_size_distribution,
             avg_resolution_days,
             resolved_issue_priority_distribution,
+            engagement: IssueEngagement::new(&filtered),
         })
     }
 }
@@ -234,7 +320,11 @@ mod tests {
 
     use crate::{
         api::{TestSchema, TODO_LIST_PROJECT_TITLE, TODO_LIST_STATUS_DONE},
-        database::issue::{GitHubIssue, GitHubProjectV2Item, GitHubProjectV2ItemConnection},
+        database::{
+            issue::{GitHubIssue, GitHubProjectV2Item, GitHubProjectV2ItemConnection},
+            reaction::GitHubReactionGroup,
+            team::GitHubTeam,
+        },
         outbound::issues::IssueState,
     };
 
@@ -427,6 +517,48 @@ mod tests {
         assert_eq!(data["issueStat"]["openIssueCount"], 2);
     }
 
+    #[tokio::test]
+    async fn open_issue_count_by_team() {
+        let schema = TestSchema::new();
+        let mut issues = create_issues(3);
+        issues[0].author = "alice".to_string();
+        issues[1].author = "bob".to_string();
+        issues[2].author = "carol".to_string();
+        schema
+            .db
+            .insert_issues(issues, "aicers", "github-dashboard-server")
+            .unwrap();
+        schema
+            .db
+            .insert_teams(
+                vec![GitHubTeam {
+                    slug: "backend".to_string(),
+                    name: "Backend".to_string(),
+                    members: vec!["alice".to_string(), "bob".to_string()],
+                }],
+                "aicers",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            issueStat(filter: {team: "backend"}) {
+                openIssueCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["issueStat"]["openIssueCount"], 2);
+
+        let query = r#"
+        {
+            issueStat(filter: {team: "frontend"}) {
+                openIssueCount
+            }
+        }"#;
+        let res = schema.execute(query).await;
+        assert_eq!(res.errors[0].message, "unknown team: frontend");
+    }
+
     #[tokio::test]
     async fn resolved_issue_count() {
         let schema = TestSchema::new();
@@ -820,4 +952,54 @@ mod tests {
         assert_eq!(dist[3]["priority"], "NONE");
         assert_eq!(dist[3]["count"], 4);
     }
+
+    #[tokio::test]
+    async fn most_upvoted_open_issues() {
+        let schema = TestSchema::new();
+        let owner = "aicers";
+        let repo = "github-dashboard-server";
+        let upvotes = |count| {
+            vec![
+                GitHubReactionGroup {
+                    content: "THUMBS_UP".to_string(),
+                    count,
+                    users: vec![],
+                },
+                GitHubReactionGroup {
+                    content: "HEART".to_string(),
+                    count: 100,
+                    users: vec![],
+                },
+            ]
+        };
+        let mut issues = create_issues(4);
+        issues[0].reactions = upvotes(1);
+        issues[1].reactions = upvotes(5);
+        // Closed issues are not listed regardless of their upvotes.
+        issues[2].reactions = upvotes(10);
+        issues[2].state = IssueState::CLOSED;
+        // Issues without upvotes are not listed.
+
+        schema.db.insert_issues(issues, owner, repo).unwrap();
+
+        let query = r"
+        {
+            issueStat(filter: {}) {
+                engagement {
+                    mostUpvotedOpenIssues {
+                        number
+                        upvoteCount
+                    }
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["issueStat"]["engagement"]["mostUpvotedOpenIssues"],
+            serde_json::json!([
+                { "number": 2, "upvoteCount": 5 },
+                { "number": 1, "upvoteCount": 1 }
+            ])
+        );
+    }
 }
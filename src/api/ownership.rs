@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Context as AnyhowContext;
+use async_graphql::{Context, Object, Result, SimpleObject};
+use num_traits::ToPrimitive;
+
+use crate::database::{ownership::FileOwnership, Database};
+
+/// The name of the root directory in `Ownership::directories`.
+const ROOT_DIRECTORY: &str = ".";
+
+#[derive(SimpleObject)]
+pub(crate) struct Ownership {
+    /// The authors of the lines of each file, ordered by path.
+    pub(crate) files: Vec<FileOwner>,
+    /// The author with the most lines in each directory, including the lines
+    /// in its subdirectories, ordered by path.
+    pub(crate) directories: Vec<DirectoryOwner>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct FileOwner {
+    pub(crate) path: String,
+    pub(crate) lines: usize,
+    /// The authors of the surviving lines, most lines first.
+    pub(crate) authors: Vec<AuthorShare>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct AuthorShare {
+    pub(crate) author: String,
+    pub(crate) lines: usize,
+    /// The fraction of the lines last changed by the author, between 0 and 1.
+    pub(crate) share: f64,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DirectoryOwner {
+    pub(crate) path: String,
+    pub(crate) lines: usize,
+    pub(crate) owner: String,
+    /// The fraction of the lines of the directory owned by `owner`.
+    pub(crate) share: f64,
+}
+
+fn share(lines: usize, total: usize) -> anyhow::Result<f64> {
+    if total == 0 {
+        return Ok(0.0);
+    }
+    Ok(lines.to_f64().context("Failed to convert usize to f64")?
+        / total.to_f64().context("Failed to convert usize to f64")?)
+}
+
+/// Returns `path` and its ancestor directories, the root last.
+fn directories(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::successors(path.rsplit_once('/').map(|(dir, _)| dir), |dir| {
+        dir.rsplit_once('/').map(|(parent, _)| parent)
+    })
+    .chain(std::iter::once(ROOT_DIRECTORY))
+}
+
+fn ownership(files: Vec<FileOwnership>) -> anyhow::Result<Ownership> {
+    let mut directory_lines: BTreeMap<String, HashMap<String, usize>> = BTreeMap::new();
+    for file in &files {
+        for dir in directories(&file.path) {
+            let lines = directory_lines.entry(dir.to_string()).or_default();
+            for author in &file.authors {
+                *lines.entry(author.author.clone()).or_default() += author.lines;
+            }
+        }
+    }
+    let directories = directory_lines
+        .into_iter()
+        .filter_map(|(path, authors)| {
+            let total = authors.values().sum();
+            let (owner, lines) = authors
+                .into_iter()
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+            Some(share(lines, total).map(|share| DirectoryOwner {
+                path,
+                lines: total,
+                owner,
+                share,
+            }))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let files = files
+        .into_iter()
+        .map(|file| {
+            let total = file.authors.iter().map(|author| author.lines).sum();
+            let authors = file
+                .authors
+                .into_iter()
+                .map(|author| {
+                    Ok(AuthorShare {
+                        share: share(author.lines, total)?,
+                        author: author.author,
+                        lines: author.lines,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Ok(FileOwner {
+                path: file.path,
+                lines: total,
+                authors,
+            })
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Ownership { files, directories })
+}
+
+#[derive(Default)]
+pub(super) struct OwnershipQuery;
+
+#[Object]
+impl OwnershipQuery {
+    /// Returns who wrote the surviving lines of the files under `pathPrefix`,
+    /// as computed by `git blame` on the checked-out branch.
+    #[allow(clippy::unused_async)]
+    async fn ownership(
+        &self,
+        ctx: &Context<'_>,
+        owner: String,
+        repo: String,
+        #[graphql(default)] path_prefix: String,
+    ) -> Result<Ownership> {
+        let db = ctx.data::<Database>()?;
+        Ok(ownership(db.file_ownerships(
+            &owner,
+            &repo,
+            &path_prefix,
+        )?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::ownership::{AuthorLines, FileOwnership},
+    };
+
+    fn file(path: &str, authors: &[(&str, usize)]) -> FileOwnership {
+        FileOwnership {
+            path: path.to_string(),
+            authors: authors
+                .iter()
+                .map(|(author, lines)| AuthorLines {
+                    author: (*author).to_string(),
+                    lines: *lines,
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn ownership_by_path_prefix() {
+        let schema = TestSchema::new();
+        for ownership in [
+            file("src/api/issue.rs", &[("Alice", 30), ("Bob", 10)]),
+            file("src/api/team.rs", &[("Bob", 40)]),
+            file("src/main.rs", &[("Carol", 20)]),
+            file("README.md", &[("Carol", 100)]),
+        ] {
+            schema
+                .db
+                .insert_file_ownership(ownership, "aicers", "github-dashboard-server")
+                .unwrap();
+        }
+
+        let query = r#"
+        {
+            ownership(owner: "aicers", repo: "github-dashboard-server", pathPrefix: "src/") {
+                files {
+                    path
+                    lines
+                    authors {
+                        author
+                        share
+                    }
+                }
+                directories {
+                    path
+                    lines
+                    owner
+                    share
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["ownership"]["files"][0],
+            serde_json::json!({
+                "path": "src/api/issue.rs",
+                "lines": 40,
+                "authors": [
+                    { "author": "Alice", "share": 0.75 },
+                    { "author": "Bob", "share": 0.25 }
+                ]
+            })
+        );
+        assert_eq!(data["ownership"]["files"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            data["ownership"]["directories"],
+            serde_json::json!([
+                { "path": ".", "lines": 100, "owner": "Bob", "share": 0.5 },
+                { "path": "src", "lines": 100, "owner": "Bob", "share": 0.5 },
+                { "path": "src/api", "lines": 80, "owner": "Bob", "share": 0.625 }
+            ])
+        );
+    }
+}
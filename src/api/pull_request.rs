@@ -1,25 +1,25 @@
-use std::fmt;
-
 use anyhow::Context as AnyhowContext;
 use async_graphql::{
     connection::{query, Connection, EmptyFields},
-    scalar, Context, Object, Result, SimpleObject,
+    scalar, ComplexObject, Context, Object, Result, SimpleObject,
 };
 use jiff::Timestamp;
 
 use crate::{
-    api::{self, DateTimeUtc},
+    api::{self, commit::LinkedCommit, history::FieldChange, issue::ReactionGroup, DateTimeUtc},
     database::{
+        self,
         pull_request::{
             GitHubCommitConnection, GitHubPRCommentConnection, GitHubPullRequestNode,
             GitHubReviewConnection, RepositoryNode,
         },
         Database, TryFromKeyValue,
     },
-    outbound::pull_requests::{PullRequestReviewState, PullRequestState},
+    outbound::pull_requests::{MergeableState, PullRequestReviewState, PullRequestState},
 };
 scalar!(PullRequestState);
 scalar!(PullRequestReviewState);
+scalar!(MergeableState);
 
 #[derive(SimpleObject, Debug)]
 pub(crate) struct PullRequestComment {
@@ -27,6 +27,7 @@ pub(crate) struct PullRequestComment {
     pub(crate) created_at: DateTimeUtc,
     pub(crate) updated_at: DateTimeUtc,
     pub(crate) author: String,
+    pub(crate) reactions: Vec<ReactionGroup>,
 }
 
 #[derive(SimpleObject)]
@@ -55,6 +56,14 @@ pub(crate) struct CommitInfo {
 }
 
 #[derive(SimpleObject)]
+pub(crate) struct ClosingIssue {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct PullRequest {
     pub(crate) id: String,
     pub(crate) owner: String,
@@ -69,12 +78,23 @@ pub(crate) struct PullRequest {
     pub(crate) closed_at: Option<DateTimeUtc>,
     pub(crate) merged_at: Option<DateTimeUtc>,
     pub(crate) author: String,
+    pub(crate) is_draft: bool,
+    pub(crate) base_ref_name: String,
+    pub(crate) head_ref_name: String,
+    pub(crate) mergeable: MergeableState,
+    pub(crate) merged_by: Option<String>,
     pub(crate) additions: i32,
     pub(crate) deletions: i32,
+    pub(crate) changed_files: i32,
+    /// The paths of the changed files.
+    pub(crate) files: Vec<String>,
     pub(crate) url: String,
+    pub(crate) milestone: Option<String>,
+    pub(crate) closing_issues_references: Vec<ClosingIssue>,
     pub(crate) labels: Vec<String>,
     pub(crate) comments_count: i32,
     pub(crate) comments: Vec<PullRequestComment>,
+    pub(crate) reactions: Vec<ReactionGroup>,
     pub(crate) review_decision: Option<PullRequestReviewState>,
     pub(crate) review_requests: Vec<String>,
     pub(crate) reviews_count: i32,
@@ -83,10 +103,41 @@ pub(crate) struct PullRequest {
     pub(crate) commits: Vec<CommitInfo>,
 }
 
+#[ComplexObject]
+impl PullRequest {
+    /// The commits of the local clones whose messages refer to the pull
+    /// request, including the commit merging it.
+    ///
+    /// Unlike `commits`, which lists the commits of the pull request on
+    /// GitHub, this is read from the checked-out branches.
+    #[allow(clippy::unused_async)]
+    async fn linked_commits(&self, ctx: &Context<'_>) -> Result<Vec<LinkedCommit>> {
+        let db = ctx.data::<Database>()?;
+        Ok(api::commit::linked_commits(
+            db,
+            &self.owner,
+            &self.repo,
+            self.number,
+        )?)
+    }
+
+    /// The changes of the title, state, labels, assignees and project
+    /// statuses found by the syncs, oldest first.
+    #[allow(clippy::unused_async)]
+    async fn history(&self, ctx: &Context<'_>) -> Result<Vec<FieldChange>> {
+        let db = ctx.data::<Database>()?;
+        Ok(db
+            .pull_request_history(&self.owner, &self.repo, self.number)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
+
 impl TryFromKeyValue for PullRequest {
     #[allow(clippy::too_many_lines)]
     fn try_from_key_value(_key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
-        let gh: GitHubPullRequestNode = bincode::deserialize(value)
+        let gh: GitHubPullRequestNode = database::decode(value)
             .with_context(|| format!("Deserialization failed for value: {value:?}"))?;
         let labels = gh.labels;
         let comments = gh
@@ -98,6 +149,7 @@ impl TryFromKeyValue for PullRequest {
                 created_at: DateTimeUtc(c.created_at),
                 updated_at: DateTimeUtc(c.updated_at),
                 author: c.author,
+                reactions: c.reactions.into_iter().map(ReactionGroup::from).collect(),
             })
             .collect();
         let reviews = gh
@@ -122,6 +174,7 @@ impl TryFromKeyValue for PullRequest {
                         created_at: DateTimeUtc(c.created_at),
                         updated_at: DateTimeUtc(c.updated_at),
                         author: c.author,
+                        reactions: c.reactions.into_iter().map(ReactionGroup::from).collect(),
                     })
                     .collect(),
             })
@@ -155,12 +208,30 @@ impl TryFromKeyValue for PullRequest {
             closed_at: gh.closed_at.map(DateTimeUtc),
             merged_at: gh.merged_at.map(DateTimeUtc),
             author: gh.author,
+            is_draft: gh.is_draft,
+            base_ref_name: gh.base_ref_name,
+            head_ref_name: gh.head_ref_name,
+            mergeable: gh.mergeable,
+            merged_by: gh.merged_by,
             additions: gh.additions,
             deletions: gh.deletions,
+            changed_files: gh.changed_files,
+            files: gh.files,
             url: gh.url,
+            milestone: gh.milestone,
+            closing_issues_references: gh
+                .closing_issues
+                .into_iter()
+                .map(|issue| ClosingIssue {
+                    owner: issue.owner,
+                    repo: issue.repo,
+                    number: issue.number,
+                })
+                .collect(),
             labels,
             comments_count: gh.comments.total_count,
             comments,
+            reactions: gh.reactions.into_iter().map(ReactionGroup::from).collect(),
             review_decision: gh.review_decision,
             assignees: gh.assignees,
             review_requests: gh.review_requests,
@@ -172,9 +243,9 @@ impl TryFromKeyValue for PullRequest {
     }
 }
 
-impl fmt::Display for PullRequest {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
+impl api::CursorKey for PullRequest {
+    fn cursor_key(&self) -> Vec<u8> {
+        database::record_key(&self.owner, &self.repo, self.number)
     }
 }
 
@@ -191,9 +262,18 @@ impl Default for GitHubPullRequestNode {
             closed_at: None,
             merged_at: None,
             author: String::new(),
+            is_draft: false,
+            base_ref_name: String::new(),
+            head_ref_name: String::new(),
+            mergeable: MergeableState::UNKNOWN,
+            merged_by: None,
             additions: 0,
             deletions: 0,
+            changed_files: 0,
+            files: vec![],
             url: String::new(),
+            milestone: None,
+            closing_issues: vec![],
             repository: RepositoryNode {
                 owner: String::new(),
                 name: String::new(),
@@ -204,6 +284,7 @@ impl Default for GitHubPullRequestNode {
                 total_count: 0,
                 nodes: vec![],
             },
+            reactions: vec![],
             review_decision: None,
             assignees: vec![],
             review_requests: vec![],
@@ -252,7 +333,7 @@ mod tests {
         GitHubCommitConnection, GitHubPRCommentConnection, GitHubPullRequestNode,
         GitHubReviewConnection, RepositoryNode,
     };
-    use crate::outbound::pull_requests::PullRequestState;
+    use crate::outbound::pull_requests::{MergeableState, PullRequestState};
 
     #[tokio::test]
     async fn pull_requests_empty() {
@@ -288,9 +369,18 @@ mod tests {
                 closed_at: None,
                 merged_at: None,
                 author: "author 1".to_string(),
+                is_draft: false,
+                base_ref_name: String::new(),
+                head_ref_name: String::new(),
+                mergeable: MergeableState::UNKNOWN,
+                merged_by: None,
                 additions: 0,
                 deletions: 0,
+                changed_files: 0,
+                files: vec![],
                 url: String::new(),
+                milestone: None,
+                closing_issues: vec![],
                 repository: RepositoryNode {
                     owner: "owner".to_string(),
                     name: "repo".to_string(),
@@ -300,6 +390,7 @@ mod tests {
                     total_count: 0,
                     nodes: vec![],
                 },
+                reactions: vec![],
                 review_decision: None,
                 assignees: vec!["assignee 1".to_string()],
                 review_requests: vec!["reviewer 1".to_string()],
@@ -323,9 +414,18 @@ mod tests {
                 closed_at: None,
                 merged_at: None,
                 author: "author 2".to_string(),
+                is_draft: false,
+                base_ref_name: String::new(),
+                head_ref_name: String::new(),
+                mergeable: MergeableState::UNKNOWN,
+                merged_by: None,
                 additions: 0,
                 deletions: 0,
+                changed_files: 0,
+                files: vec![],
                 url: String::new(),
+                milestone: None,
+                closing_issues: vec![],
                 repository: RepositoryNode {
                     owner: "owner".to_string(),
                     name: "repo".to_string(),
@@ -335,6 +435,7 @@ mod tests {
                     total_count: 0,
                     nodes: vec![],
                 },
+                reactions: vec![],
                 review_decision: None,
                 assignees: vec!["assignee 2".to_string()],
                 review_requests: vec!["reviewer 2".to_string()],
@@ -405,9 +506,18 @@ mod tests {
                 closed_at: None,
                 merged_at: None,
                 author: "author 1".to_string(),
+                is_draft: false,
+                base_ref_name: String::new(),
+                head_ref_name: String::new(),
+                mergeable: MergeableState::UNKNOWN,
+                merged_by: None,
                 additions: 0,
                 deletions: 0,
+                changed_files: 0,
+                files: vec![],
                 url: String::new(),
+                milestone: None,
+                closing_issues: vec![],
                 repository: RepositoryNode {
                     owner: "owner".to_string(),
                     name: "repo".to_string(),
@@ -417,6 +527,7 @@ mod tests {
                     total_count: 0,
                     nodes: vec![],
                 },
+                reactions: vec![],
                 review_decision: None,
                 assignees: vec!["assignee 1".to_string()],
                 review_requests: vec!["reviewer 1".to_string()],
@@ -440,9 +551,18 @@ mod tests {
                 closed_at: None,
                 merged_at: None,
                 author: "author 2".to_string(),
+                is_draft: false,
+                base_ref_name: String::new(),
+                head_ref_name: String::new(),
+                mergeable: MergeableState::UNKNOWN,
+                merged_by: None,
                 additions: 0,
                 deletions: 0,
+                changed_files: 0,
+                files: vec![],
                 url: String::new(),
+                milestone: None,
+                closing_issues: vec![],
                 repository: RepositoryNode {
                     owner: "owner".to_string(),
                     name: "repo".to_string(),
@@ -452,6 +572,7 @@ mod tests {
                     total_count: 0,
                     nodes: vec![],
                 },
+                reactions: vec![],
                 review_decision: None,
                 assignees: vec!["assignee 2".to_string()],
                 review_requests: vec!["reviewer 2".to_string()],
@@ -1,11 +1,13 @@
+use std::collections::HashSet;
+
 use anyhow::Context as AnyhowContext;
 use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
 use jiff::{SpanTotal, Unit};
 use num_traits::ToPrimitive;
 
 use crate::{
-    api::{pull_request::PullRequest, DateTimeUtc},
-    database::Iter,
+    api::{pull_request::PullRequest, team, DateTimeUtc},
+    database::{index::IndexFilter, Iter},
     outbound::pull_requests::PullRequestState,
     Database,
 };
@@ -14,6 +16,8 @@ use crate::{
 pub(crate) struct PullRequestStatFilter {
     /// Filter by pull request author.
     author: Option<String>,
+    /// Filter by the team (slug) the pull request author belongs to.
+    team: Option<String>,
     /// Filter by repository name.
     repo: Option<String>,
     /// Start of the creation datetime range. (inclusive)
@@ -22,24 +26,63 @@ pub(crate) struct PullRequestStatFilter {
     /// End of the creation datetime range. (exclusive)
     /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
     end: Option<DateTimeUtc>,
+    /// Filter by draft status.
+    draft: Option<bool>,
+    /// Filter by the name of the base branch.
+    base_branch: Option<String>,
+    /// Filter by the user who merged the pull request.
+    merged_by: Option<String>,
 }
 
 impl PullRequestStatFilter {
-    fn filter_pull_requests(&self, prs: Iter<PullRequest>) -> Vec<PullRequest> {
-        prs.into_iter()
-            .filter_map(std::result::Result::ok)
+    /// Returns the conditions of the filter that can be looked up in the
+    /// pull request index.
+    fn index_filter<'a>(&'a self, team_members: Option<&'a HashSet<String>>) -> IndexFilter<'a> {
+        IndexFilter {
+            authors: self
+                .author
+                .as_deref()
+                .map(|author| vec![author])
+                .or_else(|| {
+                    team_members.map(|members| members.iter().map(String::as_str).collect())
+                }),
+            repo: self.repo.as_deref(),
+            created_begin: self.begin.as_ref().map(|begin| begin.0),
+            created_end: self.end.as_ref().map(|end| end.0),
+            ..Default::default()
+        }
+    }
+
+    fn filter_pull_requests(
+        &self,
+        prs: Iter<PullRequest>,
+        team_members: Option<&HashSet<String>>,
+    ) -> anyhow::Result<Vec<PullRequest>> {
+        Ok(prs
+            .collect::<anyhow::Result<Vec<_>>>()?
+            .into_iter()
             .filter(|issue| {
                 self.author
                     .as_ref()
                     .is_none_or(|author| issue.author == *author)
+                    && team_members.is_none_or(|members| members.contains(&issue.author))
                     && self.repo.as_ref().is_none_or(|repo| issue.repo == *repo)
                     && self
                         .begin
                         .as_ref()
                         .is_none_or(|begin| issue.created_at >= *begin)
                     && self.end.as_ref().is_none_or(|end| issue.created_at < *end)
+                    && self.draft.is_none_or(|draft| issue.is_draft == draft)
+                    && self
+                        .base_branch
+                        .as_ref()
+                        .is_none_or(|base| issue.base_ref_name == *base)
+                    && self
+                        .merged_by
+                        .as_ref()
+                        .is_none_or(|merger| issue.merged_by.as_ref() == Some(merger))
             })
-            .collect()
+            .collect())
     }
 }
 
@@ -83,8 +126,13 @@ impl PullRequestStatQuery {
         filter: PullRequestStatFilter,
     ) -> Result<PullRequestStat> {
         let db = ctx.data::<Database>()?;
-        let prs = db.pull_requests(None, None);
-        let filtered = filter.filter_pull_requests(prs);
+        let team_members = filter
+            .team
+            .as_deref()
+            .map(|slug| team::team_members(ctx, slug))
+            .transpose()?;
+        let prs = db.pull_requests_matching(&filter.index_filter(team_members.as_ref()))?;
+        let filtered = filter.filter_pull_requests(prs, team_members.as_ref())?;
         let open_pr_count = filtered
             .iter()
             .filter(|pr| matches!(pr.state, PullRequestState::OPEN))
@@ -167,6 +215,7 @@ mod tests {
 
     use crate::api::TestSchema;
     use crate::database::pull_request::{GitHubPullRequestNode, RepositoryNode};
+    use crate::database::team::GitHubTeam;
     use crate::outbound::pull_requests::PullRequestState;
 
     fn create_pull_requests_for_repo(
@@ -350,6 +399,43 @@ mod tests {
         assert_eq!(data["pullRequestStat"]["mergedPrCount"], 1);
     }
 
+    #[tokio::test]
+    async fn pr_count_by_team() {
+        let schema = TestSchema::new();
+        let mut prs = create_pull_requests(3);
+        prs[0].author = "alice".to_string();
+        prs[1].author = "bob".to_string();
+        prs[1].state = PullRequestState::MERGED;
+        prs[2].author = "carol".to_string();
+
+        schema
+            .db
+            .insert_pull_requests(prs, "aicers", "github-dashboard-server")
+            .unwrap();
+        schema
+            .db
+            .insert_teams(
+                vec![GitHubTeam {
+                    slug: "backend".to_string(),
+                    name: "Backend".to_string(),
+                    members: vec!["alice".to_string(), "bob".to_string()],
+                }],
+                "aicers",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            pullRequestStat(filter: {team: "backend"}) {
+                openPrCount
+                mergedPrCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["pullRequestStat"]["openPrCount"], 1);
+        assert_eq!(data["pullRequestStat"]["mergedPrCount"], 1);
+    }
+
     #[tokio::test]
     async fn pr_count_with_different_states() {
         let schema = TestSchema::new();
@@ -817,4 +903,46 @@ mod tests {
             serde_json::Value::Null
         );
     }
+
+    #[tokio::test]
+    async fn pr_count_by_draft_base_branch_and_merged_by() {
+        let schema = TestSchema::new();
+        let mut prs = create_pull_requests(4);
+        prs[0].is_draft = true;
+        prs[0].base_ref_name = "main".to_string();
+        prs[1].base_ref_name = "main".to_string();
+        prs[2].base_ref_name = "release".to_string();
+        prs[2].state = PullRequestState::MERGED;
+        prs[2].merged_by = Some("alice".to_string());
+        prs[3].base_ref_name = "main".to_string();
+        prs[3].state = PullRequestState::MERGED;
+        prs[3].merged_by = Some("bob".to_string());
+
+        schema
+            .db
+            .insert_pull_requests(prs, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r#"
+        {
+            pullRequestStat(filter: {draft: false, baseBranch: "main"}) {
+                openPrCount
+                mergedPrCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["pullRequestStat"]["openPrCount"], 1);
+        assert_eq!(data["pullRequestStat"]["mergedPrCount"], 1);
+
+        let query = r#"
+        {
+            pullRequestStat(filter: {mergedBy: "alice"}) {
+                openPrCount
+                mergedPrCount
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["pullRequestStat"]["openPrCount"], 0);
+        assert_eq!(data["pullRequestStat"]["mergedPrCount"], 1);
+    }
 }
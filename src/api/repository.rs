@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+
+use crate::{
+    api::DateTimeUtc,
+    database::{
+        repository::{GitHubLanguageSize, GitHubRepositorySnapshot},
+        sync_status::{SyncEvent as SyncEventDbSchema, SyncStatus as SyncStatusDbSchema},
+        Database,
+    },
+    settings::Repository as RepoInfo,
+};
+
+#[derive(SimpleObject)]
+pub(crate) struct Repository {
+    pub(crate) owner: String,
+    pub(crate) name: String,
+    /// The most recent snapshot, if the repository has been fetched at least once.
+    pub(crate) latest: Option<RepositorySnapshot>,
+    /// Daily snapshots within the requested range, oldest first.
+    pub(crate) history: Vec<RepositorySnapshot>,
+    /// The result of the latest sync of the local clone, if any.
+    pub(crate) sync_status: Option<SyncStatus>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct SyncStatus {
+    pub(crate) synced_at: DateTimeUtc,
+    /// The branch checked out in the local clone.
+    pub(crate) branch: String,
+    /// The commit the branch points to after the sync.
+    pub(crate) head: String,
+    pub(crate) event: SyncEvent,
+}
+
+/// What the sync did to the local clone.
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum SyncEvent {
+    /// The clone already had the latest commit.
+    UpToDate,
+    /// The branch was fast-forwarded to the latest commit.
+    FastForwarded,
+    /// The clone was switched to another branch.
+    BranchSwitched,
+    /// The upstream history diverged, so the branch was hard-reset to it.
+    Reset,
+    /// The clone was corrupted, so it was cloned again.
+    Recloned,
+}
+
+impl From<SyncEventDbSchema> for SyncEvent {
+    fn from(event: SyncEventDbSchema) -> Self {
+        match event {
+            SyncEventDbSchema::UpToDate => Self::UpToDate,
+            SyncEventDbSchema::FastForwarded => Self::FastForwarded,
+            SyncEventDbSchema::BranchSwitched => Self::BranchSwitched,
+            SyncEventDbSchema::Reset => Self::Reset,
+            SyncEventDbSchema::Recloned => Self::Recloned,
+        }
+    }
+}
+
+impl From<SyncStatusDbSchema> for SyncStatus {
+    fn from(status: SyncStatusDbSchema) -> Self {
+        Self {
+            synced_at: DateTimeUtc(status.synced_at),
+            branch: status.branch,
+            head: status.head,
+            event: status.event.into(),
+        }
+    }
+}
+
+#[derive(SimpleObject, Clone)]
+pub(crate) struct RepositorySnapshot {
+    pub(crate) fetched_at: DateTimeUtc,
+    pub(crate) stargazer_count: i32,
+    pub(crate) fork_count: i32,
+    pub(crate) watcher_count: i32,
+    pub(crate) primary_language: Option<String>,
+    pub(crate) languages: Vec<LanguageSize>,
+    pub(crate) default_branch: Option<String>,
+    pub(crate) is_archived: bool,
+    pub(crate) open_issue_count: i32,
+}
+
+#[derive(SimpleObject, Clone)]
+pub(crate) struct LanguageSize {
+    pub(crate) name: String,
+    /// The number of bytes of code written in the language.
+    pub(crate) size: i64,
+}
+
+impl From<GitHubRepositorySnapshot> for RepositorySnapshot {
+    fn from(snapshot: GitHubRepositorySnapshot) -> Self {
+        Self {
+            fetched_at: DateTimeUtc(snapshot.fetched_at),
+            stargazer_count: snapshot.stargazer_count,
+            fork_count: snapshot.fork_count,
+            watcher_count: snapshot.watcher_count,
+            primary_language: snapshot.primary_language,
+            languages: snapshot
+                .languages
+                .into_iter()
+                .map(|GitHubLanguageSize { name, size }| LanguageSize { name, size })
+                .collect(),
+            default_branch: snapshot.default_branch,
+            is_archived: snapshot.is_archived,
+            open_issue_count: snapshot.open_issue_count,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(super) struct RepositoryQuery;
+
+#[Object]
+impl RepositoryQuery {
+    /// Returns the metadata of every configured repository.
+    ///
+    /// `begin` (inclusive) and `end` (exclusive) limit the snapshots in `history`.
+    #[allow(clippy::unused_async)]
+    async fn repositories(
+        &self,
+        ctx: &Context<'_>,
+        begin: Option<DateTimeUtc>,
+        end: Option<DateTimeUtc>,
+    ) -> Result<Vec<Repository>> {
+        let db = ctx.data::<Database>()?;
+        let repositories = ctx.data::<Arc<Vec<RepoInfo>>>()?;
+        repositories
+            .iter()
+            .map(|repo| {
+                let snapshots: Vec<RepositorySnapshot> = db
+                    .repository_snapshots(&repo.owner, &repo.name)?
+                    .into_iter()
+                    .map(RepositorySnapshot::from)
+                    .collect();
+                let latest = snapshots.last().cloned();
+                let history = snapshots
+                    .into_iter()
+                    .filter(|snapshot| {
+                        begin.is_none_or(|begin| snapshot.fetched_at >= begin)
+                            && end.is_none_or(|end| snapshot.fetched_at < end)
+                    })
+                    .collect();
+                let sync_status = db
+                    .sync_status(&repo.owner, &repo.name)?
+                    .map(SyncStatus::from);
+                Ok(Repository {
+                    owner: repo.owner.clone(),
+                    name: repo.name.clone(),
+                    latest,
+                    history,
+                    sync_status,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::{
+            repository::{GitHubLanguageSize, GitHubRepositorySnapshot},
+            sync_status::{SyncEvent, SyncStatus},
+        },
+        settings::Repository,
+    };
+
+    #[tokio::test]
+    async fn repositories_latest_and_history() {
+        let schema = TestSchema::with_repositories(vec![
+            Repository {
+                owner: "aicers".to_string(),
+                name: "github-dashboard-server".to_string(),
+                branch: None,
+                protocol: None,
+            },
+            Repository {
+                owner: "aicers".to_string(),
+                name: "github-dashboard-client".to_string(),
+                branch: None,
+                protocol: None,
+            },
+        ]);
+        for (date, stars) in [("2025-01-05T09:00:00Z", 10), ("2025-01-06T09:00:00Z", 12)] {
+            let snapshot = GitHubRepositorySnapshot {
+                fetched_at: date.parse().unwrap(),
+                stargazer_count: stars,
+                primary_language: Some("Rust".to_string()),
+                languages: vec![GitHubLanguageSize {
+                    name: "Rust".to_string(),
+                    size: 1024,
+                }],
+                ..Default::default()
+            };
+            schema
+                .db
+                .insert_repository_snapshot(snapshot, "aicers", "github-dashboard-server")
+                .unwrap();
+        }
+        schema
+            .db
+            .insert_sync_status(
+                SyncStatus {
+                    synced_at: "2025-01-06T10:00:00Z".parse().unwrap(),
+                    branch: "develop".to_string(),
+                    head: "0123abcd".to_string(),
+                    event: SyncEvent::Reset,
+                },
+                "aicers",
+                "github-dashboard-server",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            repositories(begin: "2025-01-06T00:00:00Z") {
+                name
+                latest {
+                    stargazerCount
+                }
+                history {
+                    stargazerCount
+                }
+                syncStatus {
+                    branch
+                    head
+                    event
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["repositories"],
+            serde_json::json!([
+                {
+                    "name": "github-dashboard-server",
+                    "latest": { "stargazerCount": 12 },
+                    "history": [{ "stargazerCount": 12 }],
+                    "syncStatus": {
+                        "branch": "develop",
+                        "head": "0123abcd",
+                        "event": "RESET"
+                    }
+                },
+                {
+                    "name": "github-dashboard-client",
+                    "latest": null,
+                    "history": [],
+                    "syncStatus": null
+                }
+            ])
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context as AnyhowContext;
+use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use jiff::{SpanTotal, Unit};
+use num_traits::ToPrimitive;
+
+use crate::{
+    api::{
+        code_scanning_alert::{CodeScanningAlert, CodeScanningAlertState},
+        vulnerability_alert::VulnerabilityAlert,
+        DateTimeUtc,
+    },
+    database::Iter,
+    outbound::vulnerability_alerts::{RepositoryVulnerabilityAlertState, SecurityAdvisorySeverity},
+    Database,
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
+enum AlertSeverity {
+    Critical,
+    High,
+    Moderate,
+    Low,
+    Unknown,
+}
+
+impl AlertSeverity {
+    /// Returns the severity of the `security_severity_level` of a code
+    /// scanning rule.
+    fn from_security_severity(level: Option<&str>) -> Self {
+        match level {
+            Some("critical") => Self::Critical,
+            Some("high") => Self::High,
+            Some("medium") => Self::Moderate,
+            Some("low") => Self::Low,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl From<&SecurityAdvisorySeverity> for AlertSeverity {
+    fn from(severity: &SecurityAdvisorySeverity) -> Self {
+        match severity {
+            SecurityAdvisorySeverity::CRITICAL => Self::Critical,
+            SecurityAdvisorySeverity::HIGH => Self::High,
+            SecurityAdvisorySeverity::MODERATE => Self::Moderate,
+            SecurityAdvisorySeverity::LOW => Self::Low,
+            SecurityAdvisorySeverity::Other(_) => Self::Unknown,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+struct AlertSeverityCount {
+    severity: AlertSeverity,
+    count: usize,
+}
+
+#[derive(InputObject, Debug)]
+pub(crate) struct SecurityStatFilter {
+    /// Filter by repository name.
+    repo: Option<String>,
+    /// Start of the creation datetime range. (inclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    begin: Option<DateTimeUtc>,
+    /// End of the creation datetime range. (exclusive)
+    /// Example format: "yyyy-MM-ddTHH:mm:ssZ"
+    end: Option<DateTimeUtc>,
+}
+
+impl SecurityStatFilter {
+    fn matches(&self, repo: &str, created_at: DateTimeUtc) -> bool {
+        self.repo.as_ref().is_none_or(|filter| filter == repo)
+            && self.begin.is_none_or(|begin| created_at >= begin)
+            && self.end.is_none_or(|end| created_at < end)
+    }
+
+    fn filter_alerts(
+        &self,
+        alerts: Iter<VulnerabilityAlert>,
+    ) -> anyhow::Result<Vec<VulnerabilityAlert>> {
+        alerts
+            .filter_map(|alert| {
+                alert
+                    .map(|alert| self.matches(&alert.repo, alert.created_at).then_some(alert))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    fn filter_code_scanning_alerts(
+        &self,
+        alerts: Iter<CodeScanningAlert>,
+    ) -> anyhow::Result<Vec<CodeScanningAlert>> {
+        alerts
+            .filter_map(|alert| {
+                alert
+                    .map(|alert| self.matches(&alert.repo, alert.created_at).then_some(alert))
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+/// Returns the number of alerts per severity, most severe first.
+fn severity_distribution(
+    severities: impl Iterator<Item = AlertSeverity>,
+) -> Vec<AlertSeverityCount> {
+    severities
+        .fold(BTreeMap::new(), |mut acc, severity| {
+            *acc.entry(severity).or_insert(0) += 1;
+            acc
+        })
+        .into_iter()
+        .map(|(severity, count)| AlertSeverityCount { severity, count })
+        .collect()
+}
+
+/// Returns the average number of days from the creation of an alert until it
+/// is fixed, or `None` if no alert has been fixed.
+fn mean_time_to_remediate_days(
+    alerts: impl Iterator<Item = (DateTimeUtc, Option<DateTimeUtc>)>,
+) -> Result<Option<f64>> {
+    let remediation_days: Vec<f64> = alerts
+        .filter_map(|(created_at, fixed_at)| {
+            let span = created_at.0.until(fixed_at?.0).ok()?;
+            span.total(SpanTotal::from(Unit::Day).days_are_24_hours())
+                .ok()
+        })
+        .collect();
+    if remediation_days.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(
+        remediation_days.iter().sum::<f64>()
+            / remediation_days
+                .len()
+                .to_f64()
+                .context("Failed to convert usize to f64")?,
+    ))
+}
+
+#[derive(Default)]
+pub(super) struct SecurityStatQuery {}
+
+#[derive(SimpleObject)]
+struct SecurityStat {
+    /// The number of open vulnerability alerts.
+    open_alert_count: i32,
+    /// The number of open vulnerability alerts per severity, most severe first.
+    open_alert_severity_distribution: Vec<AlertSeverityCount>,
+    /// The average number of days from the creation of an alert until it is
+    /// fixed.
+    ///
+    /// This field is `None` if no alert has been fixed.
+    mean_time_to_remediate_days: Option<f64>,
+    /// The number of open code scanning alerts.
+    open_code_scanning_alert_count: i32,
+    /// The number of open code scanning alerts per security severity, most
+    /// severe first. Alerts of rules without a security severity are counted
+    /// as `UNKNOWN`.
+    open_code_scanning_alert_severity_distribution: Vec<AlertSeverityCount>,
+    /// The average number of days from the creation of a code scanning alert
+    /// until it is fixed.
+    ///
+    /// This field is `None` if no code scanning alert has been fixed.
+    code_scanning_mean_time_to_remediate_days: Option<f64>,
+}
+
+#[Object]
+impl SecurityStatQuery {
+    #[allow(clippy::unused_async)]
+    async fn security_stat(
+        &self,
+        ctx: &Context<'_>,
+        filter: SecurityStatFilter,
+    ) -> Result<SecurityStat> {
+        let db = ctx.data::<Database>()?;
+        let alerts = filter.filter_alerts(db.vulnerability_alerts(None, None))?;
+        let open_alerts: Vec<_> = alerts
+            .iter()
+            .filter(|alert| matches!(alert.state, RepositoryVulnerabilityAlertState::OPEN))
+            .collect();
+
+        let code_scanning_alerts =
+            filter.filter_code_scanning_alerts(db.code_scanning_alerts(None, None))?;
+        let open_code_scanning_alerts: Vec<_> = code_scanning_alerts
+            .iter()
+            .filter(|alert| alert.state == CodeScanningAlertState::Open)
+            .collect();
+
+        Ok(SecurityStat {
+            open_alert_count: open_alerts.len().try_into()?,
+            open_alert_severity_distribution: severity_distribution(
+                open_alerts
+                    .iter()
+                    .map(|alert| AlertSeverity::from(&alert.severity)),
+            ),
+            mean_time_to_remediate_days: mean_time_to_remediate_days(
+                alerts
+                    .iter()
+                    .map(|alert| (alert.created_at, alert.fixed_at)),
+            )?,
+            open_code_scanning_alert_count: open_code_scanning_alerts.len().try_into()?,
+            open_code_scanning_alert_severity_distribution: severity_distribution(
+                open_code_scanning_alerts.iter().map(|alert| {
+                    AlertSeverity::from_security_severity(alert.security_severity.as_deref())
+                }),
+            ),
+            code_scanning_mean_time_to_remediate_days: mean_time_to_remediate_days(
+                code_scanning_alerts
+                    .iter()
+                    .map(|alert| (alert.created_at, alert.fixed_at)),
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::{
+            code_scanning_alert::{CodeScanningAlertState, GitHubCodeScanningAlert},
+            vulnerability_alert::GitHubVulnerabilityAlert,
+        },
+        outbound::vulnerability_alerts::{
+            RepositoryVulnerabilityAlertState, SecurityAdvisorySeverity,
+        },
+    };
+
+    fn create_alert(
+        number: i32,
+        severity: SecurityAdvisorySeverity,
+        created_at: &str,
+        fixed_at: Option<&str>,
+    ) -> GitHubVulnerabilityAlert {
+        GitHubVulnerabilityAlert {
+            number,
+            severity,
+            state: if fixed_at.is_some() {
+                RepositoryVulnerabilityAlertState::FIXED
+            } else {
+                RepositoryVulnerabilityAlertState::OPEN
+            },
+            created_at: created_at.parse().unwrap(),
+            fixed_at: fixed_at.map(|date| date.parse().unwrap()),
+            package: String::new(),
+            dismissed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn security_stat_empty() {
+        let schema = TestSchema::new();
+        let query = r"
+        {
+            securityStat(filter: {}) {
+                openAlertCount
+                meanTimeToRemediateDays
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(data["securityStat"]["openAlertCount"], 0);
+        assert!(data["securityStat"]["meanTimeToRemediateDays"].is_null());
+    }
+
+    #[tokio::test]
+    async fn open_alerts_and_remediation_by_repo_and_date() {
+        let schema = TestSchema::new();
+        let server_alerts = vec![
+            create_alert(
+                1,
+                SecurityAdvisorySeverity::HIGH,
+                "2025-01-01T00:00:00Z",
+                None,
+            ),
+            create_alert(
+                2,
+                SecurityAdvisorySeverity::CRITICAL,
+                "2025-01-02T00:00:00Z",
+                None,
+            ),
+            create_alert(
+                3,
+                SecurityAdvisorySeverity::HIGH,
+                "2025-01-03T00:00:00Z",
+                None,
+            ),
+            create_alert(
+                4,
+                SecurityAdvisorySeverity::LOW,
+                "2025-01-01T00:00:00Z",
+                Some("2025-01-03T00:00:00Z"),
+            ),
+            create_alert(
+                5,
+                SecurityAdvisorySeverity::LOW,
+                "2025-01-01T00:00:00Z",
+                Some("2025-01-05T00:00:00Z"),
+            ),
+            create_alert(
+                6,
+                SecurityAdvisorySeverity::MODERATE,
+                "2024-12-01T00:00:00Z",
+                None,
+            ),
+        ];
+        let client_alerts = vec![create_alert(
+            1,
+            SecurityAdvisorySeverity::MODERATE,
+            "2025-01-01T00:00:00Z",
+            None,
+        )];
+        schema
+            .db
+            .insert_vulnerability_alerts(server_alerts, "aicers", "github-dashboard-server")
+            .unwrap();
+        schema
+            .db
+            .insert_vulnerability_alerts(client_alerts, "aicers", "github-dashboard-client")
+            .unwrap();
+
+        let query = r#"
+        {
+            securityStat(filter: {repo: "github-dashboard-server", begin: "2025-01-01T00:00:00Z"}) {
+                openAlertCount
+                openAlertSeverityDistribution {
+                    severity
+                    count
+                }
+                meanTimeToRemediateDays
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["securityStat"],
+            serde_json::json!({
+                "openAlertCount": 3,
+                "openAlertSeverityDistribution": [
+                    { "severity": "CRITICAL", "count": 1 },
+                    { "severity": "HIGH", "count": 2 }
+                ],
+                "meanTimeToRemediateDays": 3.0
+            })
+        );
+    }
+
+    fn create_code_scanning_alert(
+        number: i32,
+        security_severity: Option<&str>,
+        created_at: &str,
+        fixed_at: Option<&str>,
+    ) -> GitHubCodeScanningAlert {
+        GitHubCodeScanningAlert {
+            number,
+            rule: String::new(),
+            tool: "CodeQL".to_string(),
+            security_severity: security_severity.map(ToString::to_string),
+            state: if fixed_at.is_some() {
+                CodeScanningAlertState::Fixed
+            } else {
+                CodeScanningAlertState::Open
+            },
+            created_at: created_at.parse().unwrap(),
+            fixed_at: fixed_at.map(|date| date.parse().unwrap()),
+            dismissed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn open_code_scanning_alerts_and_remediation() {
+        let schema = TestSchema::new();
+        let alerts = vec![
+            create_code_scanning_alert(1, Some("high"), "2025-01-01T00:00:00Z", None),
+            create_code_scanning_alert(2, Some("medium"), "2025-01-01T00:00:00Z", None),
+            create_code_scanning_alert(3, None, "2025-01-01T00:00:00Z", None),
+            create_code_scanning_alert(
+                4,
+                Some("high"),
+                "2025-01-01T00:00:00Z",
+                Some("2025-01-02T00:00:00Z"),
+            ),
+        ];
+        schema
+            .db
+            .insert_code_scanning_alerts(alerts, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r"
+        {
+            securityStat(filter: {}) {
+                openAlertCount
+                openCodeScanningAlertCount
+                openCodeScanningAlertSeverityDistribution {
+                    severity
+                    count
+                }
+                codeScanningMeanTimeToRemediateDays
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["securityStat"],
+            serde_json::json!({
+                "openAlertCount": 0,
+                "openCodeScanningAlertCount": 3,
+                "openCodeScanningAlertSeverityDistribution": [
+                    { "severity": "HIGH", "count": 1 },
+                    { "severity": "MODERATE", "count": 1 },
+                    { "severity": "UNKNOWN", "count": 1 }
+                ],
+                "codeScanningMeanTimeToRemediateDays": 1.0
+            })
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+use jiff::tz::TimeZone;
+
+use crate::{
+    api::DateTimeUtc,
+    database::{
+        snapshot::{DailySnapshot, StateSnapshot},
+        Database,
+    },
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+enum SnapshotMetric {
+    OpenIssues,
+    ClosedIssues,
+    OpenPullRequests,
+    MergedPullRequests,
+    ClosedPullRequests,
+}
+
+impl SnapshotMetric {
+    /// Returns the counts of the records the metric counts in a snapshot.
+    fn select(self, snapshot: &DailySnapshot) -> Option<&StateSnapshot> {
+        match self {
+            Self::OpenIssues => snapshot.issues.get("OPEN"),
+            Self::ClosedIssues => snapshot.issues.get("CLOSED"),
+            Self::OpenPullRequests => snapshot.pull_requests.get("OPEN"),
+            Self::MergedPullRequests => snapshot.pull_requests.get("MERGED"),
+            Self::ClosedPullRequests => snapshot.pull_requests.get("CLOSED"),
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug, Default)]
+enum SnapshotGroup {
+    #[default]
+    None,
+    Repo,
+    Label,
+    Assignee,
+    ProjectStatus,
+}
+
+#[derive(SimpleObject)]
+struct SnapshotPoint {
+    /// The start of the day (00:00 UTC) the snapshots were taken on.
+    date: DateTimeUtc,
+    /// The counts per group, ordered by group.
+    counts: Vec<SnapshotCount>,
+}
+
+#[derive(SimpleObject)]
+struct SnapshotCount {
+    /// The repository as `owner/name`, the label, the assignee or the project
+    /// status, or `None` if the series is not grouped.
+    group: Option<String>,
+    count: usize,
+}
+
+#[derive(Default)]
+pub(super) struct SnapshotQuery;
+
+#[Object]
+impl SnapshotQuery {
+    /// Returns the daily counts of issues or pull requests, taken after each
+    /// sync, summed over the configured repositories.
+    ///
+    /// `begin` (inclusive) and `end` (exclusive) limit the days the snapshots
+    /// were taken on. A record with several labels, assignees or project
+    /// statuses is counted under each of them, and a record with none is not
+    /// counted when grouped by them.
+    #[allow(clippy::unused_async)]
+    async fn snapshot_series(
+        &self,
+        ctx: &Context<'_>,
+        metric: SnapshotMetric,
+        #[graphql(default)] group_by: SnapshotGroup,
+        begin: Option<DateTimeUtc>,
+        end: Option<DateTimeUtc>,
+    ) -> Result<Vec<SnapshotPoint>> {
+        let db = ctx.data::<Database>()?;
+        let begin = begin.map(|begin| begin.0.to_zoned(TimeZone::UTC).date());
+        let end = end.map(|end| {
+            let end = end.0.to_zoned(TimeZone::UTC);
+            // A day starting before `end` is within the range.
+            if end.time() == jiff::civil::Time::midnight() {
+                Ok(end.date())
+            } else {
+                end.date().tomorrow()
+            }
+        });
+        let end = end.transpose()?;
+
+        let mut points: BTreeMap<_, BTreeMap<Option<String>, usize>> = BTreeMap::new();
+        for (date, snapshot) in db.daily_snapshots(begin, end)? {
+            let counts = points.entry(date).or_default();
+            let Some(selected) = metric.select(&snapshot) else {
+                continue;
+            };
+            let grouped = |groups: &BTreeMap<String, usize>| {
+                groups
+                    .iter()
+                    .map(|(group, count)| (Some(group.clone()), *count))
+                    .collect::<Vec<_>>()
+            };
+            let groups = match group_by {
+                SnapshotGroup::None => vec![(None, selected.count)],
+                SnapshotGroup::Repo => vec![(
+                    Some(format!("{}/{}", snapshot.owner, snapshot.repo)),
+                    selected.count,
+                )],
+                SnapshotGroup::Label => grouped(&selected.labels),
+                SnapshotGroup::Assignee => grouped(&selected.assignees),
+                SnapshotGroup::ProjectStatus => grouped(&selected.project_statuses),
+            };
+            for (group, count) in groups {
+                *counts.entry(group).or_default() += count;
+            }
+        }
+
+        points
+            .into_iter()
+            .map(|(date, counts)| {
+                Ok(SnapshotPoint {
+                    date: DateTimeUtc(date.to_zoned(TimeZone::UTC)?.timestamp()),
+                    counts: counts
+                        .into_iter()
+                        .map(|(group, count)| SnapshotCount { group, count })
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::{
+            issue::{GitHubProjectV2Item, GitHubProjectV2ItemConnection},
+            GitHubIssue,
+        },
+        outbound::issues::IssueState,
+    };
+
+    fn issue(number: i32, state: IssueState, labels: &[&str], status: Option<&str>) -> GitHubIssue {
+        GitHubIssue {
+            number,
+            state,
+            labels: labels.iter().map(ToString::to_string).collect(),
+            project_items: GitHubProjectV2ItemConnection {
+                total_count: 1,
+                nodes: status
+                    .map(|status| GitHubProjectV2Item {
+                        project_id: String::new(),
+                        project_title: "to-do list".to_string(),
+                        id: String::new(),
+                        todo_status: Some(status.to_string()),
+                        todo_priority: None,
+                        todo_size: None,
+                        todo_initiation_option: None,
+                        todo_pending_days: None,
+                    })
+                    .into_iter()
+                    .collect(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_series() {
+        let schema = TestSchema::new();
+        let db = &schema.db;
+        db.insert_issues(
+            vec![
+                issue(1, IssueState::OPEN, &["bug"], Some("Todo")),
+                issue(2, IssueState::OPEN, &["bug", "ui"], None),
+            ],
+            "aicers",
+            "frontary",
+        )
+        .unwrap();
+        db.insert_daily_snapshot(
+            "aicers",
+            "frontary",
+            "2025-01-06T09:00:00Z".parse().unwrap(),
+        )
+        .unwrap();
+        db.insert_issues(
+            vec![issue(2, IssueState::CLOSED, &["bug", "ui"], None)],
+            "aicers",
+            "frontary",
+        )
+        .unwrap();
+        db.insert_issues(
+            vec![issue(1, IssueState::OPEN, &[], Some("Done"))],
+            "aicers",
+            "review",
+        )
+        .unwrap();
+        for repo in ["frontary", "review"] {
+            db.insert_daily_snapshot("aicers", repo, "2025-01-07T09:00:00Z".parse().unwrap())
+                .unwrap();
+        }
+
+        let query = r#"
+        {
+            total: snapshotSeries(metric: OPEN_ISSUES) {
+                date
+                counts {
+                    group
+                    count
+                }
+            }
+            labels: snapshotSeries(metric: OPEN_ISSUES, groupBy: LABEL, begin: "2025-01-06T00:00:00Z", end: "2025-01-07T00:00:00Z") {
+                counts {
+                    group
+                    count
+                }
+            }
+            statuses: snapshotSeries(metric: OPEN_ISSUES, groupBy: PROJECT_STATUS, begin: "2025-01-07T00:00:00Z") {
+                counts {
+                    group
+                    count
+                }
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["total"],
+            serde_json::json!([
+                { "date": "2025-01-06T00:00:00Z", "counts": [{ "group": null, "count": 2 }] },
+                { "date": "2025-01-07T00:00:00Z", "counts": [{ "group": null, "count": 2 }] },
+            ])
+        );
+        assert_eq!(
+            data["labels"],
+            serde_json::json!([{
+                "counts": [{ "group": "bug", "count": 2 }, { "group": "ui", "count": 1 }]
+            }])
+        );
+        assert_eq!(
+            data["statuses"],
+            serde_json::json!([{
+                "counts": [{ "group": "Done", "count": 1 }, { "group": "Todo", "count": 1 }]
+            }])
+        );
+    }
+}
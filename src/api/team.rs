@@ -0,0 +1,146 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::{anyhow, Context as AnyhowContext};
+use async_graphql::{Context, Object, Result, SimpleObject};
+
+use crate::{
+    database::{self, team::GitHubTeam, Database, TryFromKeyValue},
+    settings::Team as TeamInfo,
+};
+
+#[derive(SimpleObject, Debug)]
+pub(crate) struct Team {
+    /// The organization of the team, or `None` if the team is defined only in
+    /// the configuration file.
+    pub(crate) organization: Option<String>,
+    pub(crate) slug: String,
+    pub(crate) name: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl TryFromKeyValue for Team {
+    fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
+        let (organization, _) = std::str::from_utf8(key)
+            .ok()
+            .and_then(|key| key.split_once('/'))
+            .ok_or_else(|| anyhow!("invalid key in database: {key:02x?}"))?;
+        let team: GitHubTeam = database::decode(value)
+            .with_context(|| format!("Deserialization failed for value: {value:?}"))?;
+        Ok(Team {
+            organization: Some(organization.to_string()),
+            slug: team.slug,
+            name: team.name,
+            members: team.members,
+        })
+    }
+}
+
+/// Returns the teams synced from GitHub, with the members from the
+/// configuration file added.
+pub(super) fn load_teams(ctx: &Context<'_>) -> Result<Vec<Team>> {
+    let db = ctx.data::<Database>()?;
+    let team_infos = ctx.data::<Arc<Vec<TeamInfo>>>()?;
+    let mut teams = db.teams().collect::<anyhow::Result<Vec<_>>>()?;
+    for team_info in team_infos.iter() {
+        let mut found = false;
+        for team in teams.iter_mut().filter(|team| team.slug == team_info.slug) {
+            team.members.extend(team_info.members.iter().cloned());
+            team.members.sort_unstable();
+            team.members.dedup();
+            found = true;
+        }
+        if !found {
+            teams.push(Team {
+                organization: None,
+                slug: team_info.slug.clone(),
+                name: team_info.slug.clone(),
+                members: team_info.members.clone(),
+            });
+        }
+    }
+    Ok(teams)
+}
+
+/// Returns the logins of the members of the teams with the given slug.
+///
+/// # Errors
+///
+/// Returns an error if no team has the given slug.
+pub(super) fn team_members(ctx: &Context<'_>, slug: &str) -> Result<HashSet<String>> {
+    let teams: Vec<Team> = load_teams(ctx)?
+        .into_iter()
+        .filter(|team| team.slug == slug)
+        .collect();
+    if teams.is_empty() {
+        return Err(format!("unknown team: {slug}").into());
+    }
+    Ok(teams.into_iter().flat_map(|team| team.members).collect())
+}
+
+#[derive(Default)]
+pub(super) struct TeamQuery;
+
+#[Object]
+impl TeamQuery {
+    /// Returns the teams of the organizations owning the configured
+    /// repositories, including the teams defined in the configuration file.
+    #[allow(clippy::unused_async)]
+    async fn teams(&self, ctx: &Context<'_>) -> Result<Vec<Team>> {
+        load_teams(ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::TestSchema, database::team::GitHubTeam, settings::Team};
+
+    #[tokio::test]
+    async fn teams_with_config_override() {
+        let schema = TestSchema::with_teams(vec![
+            Team {
+                slug: "backend".to_string(),
+                members: vec!["carol".to_string(), "alice".to_string()],
+            },
+            Team {
+                slug: "contractors".to_string(),
+                members: vec!["dave".to_string()],
+            },
+        ]);
+        schema
+            .db
+            .insert_teams(
+                vec![GitHubTeam {
+                    slug: "backend".to_string(),
+                    name: "Backend".to_string(),
+                    members: vec!["alice".to_string(), "bob".to_string()],
+                }],
+                "aicers",
+            )
+            .unwrap();
+
+        let query = r"
+        {
+            teams {
+                organization
+                slug
+                members
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["teams"],
+            serde_json::json!([
+                {
+                    "organization": "aicers",
+                    "slug": "backend",
+                    "members": ["alice", "bob", "carol"]
+                },
+                {
+                    "organization": null,
+                    "slug": "contractors",
+                    "members": ["dave"]
+                }
+            ])
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use async_graphql::{Context, Enum, Object, Result, SimpleObject};
+
+use crate::{
+    database::{
+        todo::{CodeTodo as CodeTodoDbSchema, TodoMarker as TodoMarkerDbSchema},
+        Database,
+    },
+    outbound::issues::IssueState,
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) enum TodoMarker {
+    Todo,
+    Fixme,
+    Xxx,
+}
+
+impl From<TodoMarkerDbSchema> for TodoMarker {
+    fn from(marker: TodoMarkerDbSchema) -> Self {
+        match marker {
+            TodoMarkerDbSchema::Todo => Self::Todo,
+            TodoMarkerDbSchema::Fixme => Self::Fixme,
+            TodoMarkerDbSchema::Xxx => Self::Xxx,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct CodeTodo {
+    pub(crate) path: String,
+    pub(crate) line: usize,
+    pub(crate) marker: TodoMarker,
+    /// The line containing the marker, trimmed.
+    pub(crate) text: String,
+    /// The issue the marker refers to, if any.
+    pub(crate) issue: Option<LinkedIssue>,
+    /// Whether the marker refers to an issue that is already closed.
+    pub(crate) stale: bool,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct LinkedIssue {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+    /// The title of the issue, or `None` if the issue has not been fetched.
+    pub(crate) title: Option<String>,
+    /// The state of the issue, or `None` if the issue has not been fetched.
+    pub(crate) state: Option<IssueState>,
+}
+
+fn code_todo(db: &Database, todo: CodeTodoDbSchema) -> anyhow::Result<CodeTodo> {
+    let issue = todo
+        .issue
+        .map(|reference| {
+            let issue = db.issue(&reference.owner, &reference.repo, reference.number)?;
+            anyhow::Ok(LinkedIssue {
+                owner: reference.owner,
+                repo: reference.repo,
+                number: reference.number,
+                title: issue.as_ref().map(|issue| issue.title.clone()),
+                state: issue.map(|issue| issue.state),
+            })
+        })
+        .transpose()?;
+    let stale = issue
+        .as_ref()
+        .is_some_and(|issue| issue.state == Some(IssueState::CLOSED));
+    Ok(CodeTodo {
+        path: todo.path,
+        line: todo.line,
+        marker: todo.marker.into(),
+        text: todo.text,
+        issue,
+        stale,
+    })
+}
+
+#[derive(Default)]
+pub(super) struct TodoQuery;
+
+#[Object]
+impl TodoQuery {
+    /// Returns the `TODO`, `FIXME` and `XXX` markers in the files of the
+    /// checked-out branch, ordered by path and line.
+    ///
+    /// If `staleOnly` is true, only the markers referring to closed issues are
+    /// returned.
+    #[allow(clippy::unused_async)]
+    async fn code_todos(
+        &self,
+        ctx: &Context<'_>,
+        owner: String,
+        repo: String,
+        #[graphql(default)] stale_only: bool,
+    ) -> Result<Vec<CodeTodo>> {
+        let db = ctx.data::<Database>()?;
+        let todos = db
+            .code_todos(&owner, &repo)?
+            .into_iter()
+            .map(|todo| code_todo(db, todo))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(todos
+            .into_iter()
+            .filter(|todo| !stale_only || todo.stale)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::{
+            todo::{CodeTodo, IssueReference, TodoMarker},
+            GitHubIssue,
+        },
+        outbound::issues::IssueState,
+    };
+
+    fn todo(line: usize, issue: Option<(&str, i32)>) -> CodeTodo {
+        CodeTodo {
+            path: "src/main.rs".to_string(),
+            line,
+            marker: TodoMarker::Todo,
+            text: String::new(),
+            issue: issue.map(|(repo, number)| IssueReference {
+                owner: "aicers".to_string(),
+                repo: repo.to_string(),
+                number,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn todos_with_closed_issues() {
+        let schema = TestSchema::new();
+        schema
+            .db
+            .insert_issues(
+                vec![
+                    GitHubIssue {
+                        number: 1,
+                        title: "open".to_string(),
+                        state: IssueState::OPEN,
+                        ..Default::default()
+                    },
+                    GitHubIssue {
+                        number: 2,
+                        title: "closed".to_string(),
+                        state: IssueState::CLOSED,
+                        ..Default::default()
+                    },
+                ],
+                "aicers",
+                "frontary",
+            )
+            .unwrap();
+        schema
+            .db
+            .insert_code_todos(
+                vec![
+                    todo(10, Some(("frontary", 1))),
+                    todo(20, Some(("frontary", 2))),
+                    todo(30, Some(("review", 3))),
+                    todo(40, None),
+                ],
+                "aicers",
+                "frontary",
+            )
+            .unwrap();
+
+        let query = r#"
+        {
+            all: codeTodos(owner: "aicers", repo: "frontary") {
+                line
+                stale
+                issue {
+                    title
+                    state
+                }
+            }
+            stale: codeTodos(owner: "aicers", repo: "frontary", staleOnly: true) {
+                line
+            }
+        }"#;
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["all"],
+            serde_json::json!([
+                { "line": 10, "stale": false, "issue": { "title": "open", "state": "OPEN" } },
+                { "line": 20, "stale": true, "issue": { "title": "closed", "state": "CLOSED" } },
+                { "line": 30, "stale": false, "issue": { "title": null, "state": null } },
+                { "line": 40, "stale": false, "issue": null },
+            ])
+        );
+        assert_eq!(data["stale"], serde_json::json!([{ "line": 20 }]));
+    }
+}
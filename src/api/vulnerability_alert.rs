@@ -0,0 +1,164 @@
+use anyhow::Context as AnyhowContext;
+use async_graphql::{
+    connection::{query, Connection, EmptyFields},
+    scalar, Context, Object, Result, SimpleObject,
+};
+
+use crate::{
+    api::{self, DateTimeUtc},
+    database::{self, vulnerability_alert::GitHubVulnerabilityAlert, Database, TryFromKeyValue},
+    outbound::vulnerability_alerts::{RepositoryVulnerabilityAlertState, SecurityAdvisorySeverity},
+};
+
+scalar!(RepositoryVulnerabilityAlertState);
+scalar!(SecurityAdvisorySeverity);
+
+#[derive(SimpleObject)]
+pub(crate) struct VulnerabilityAlert {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+    /// The name of the vulnerable package.
+    pub(crate) package: String,
+    pub(crate) severity: SecurityAdvisorySeverity,
+    pub(crate) state: RepositoryVulnerabilityAlertState,
+    pub(crate) created_at: DateTimeUtc,
+    pub(crate) fixed_at: Option<DateTimeUtc>,
+    pub(crate) dismissed_at: Option<DateTimeUtc>,
+}
+
+impl TryFromKeyValue for VulnerabilityAlert {
+    fn try_from_key_value(key: &[u8], value: &[u8]) -> anyhow::Result<Self> {
+        let (owner, repo, number) = database::parse_key(key)
+            .with_context(|| format!("invalid key in database: {key:02x?}"))?;
+        let alert: GitHubVulnerabilityAlert = database::decode(value)
+            .with_context(|| format!("Deserialization failed for value: {value:?}"))?;
+        Ok(Self {
+            owner,
+            repo,
+            number,
+            package: alert.package,
+            severity: alert.severity,
+            state: alert.state,
+            created_at: DateTimeUtc(alert.created_at),
+            fixed_at: alert.fixed_at.map(DateTimeUtc),
+            dismissed_at: alert.dismissed_at.map(DateTimeUtc),
+        })
+    }
+}
+
+impl api::CursorKey for VulnerabilityAlert {
+    fn cursor_key(&self) -> Vec<u8> {
+        database::record_key(&self.owner, &self.repo, self.number)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct VulnerabilityAlertQuery;
+
+#[Object]
+impl VulnerabilityAlertQuery {
+    /// Returns the Dependabot alerts of the configured repositories.
+    async fn vulnerability_alerts(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<Connection<String, VulnerabilityAlert, EmptyFields, EmptyFields>> {
+        query(
+            after,
+            before,
+            first,
+            last,
+            |after, before, first, last| async move {
+                api::load_connection(
+                    ctx,
+                    Database::vulnerability_alerts,
+                    after,
+                    before,
+                    first,
+                    last,
+                )
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::TestSchema,
+        database::vulnerability_alert::GitHubVulnerabilityAlert,
+        outbound::vulnerability_alerts::{
+            RepositoryVulnerabilityAlertState, SecurityAdvisorySeverity,
+        },
+    };
+
+    #[tokio::test]
+    async fn vulnerability_alerts_first() {
+        let schema = TestSchema::new();
+        let alerts = vec![
+            GitHubVulnerabilityAlert {
+                number: 1,
+                package: "openssl".to_string(),
+                severity: SecurityAdvisorySeverity::HIGH,
+                state: RepositoryVulnerabilityAlertState::FIXED,
+                created_at: "2025-01-01T00:00:00Z".parse().unwrap(),
+                fixed_at: Some("2025-01-03T00:00:00Z".parse().unwrap()),
+                dismissed_at: None,
+            },
+            GitHubVulnerabilityAlert {
+                number: 2,
+                package: "time".to_string(),
+                severity: SecurityAdvisorySeverity::LOW,
+                state: RepositoryVulnerabilityAlertState::OPEN,
+                created_at: "2025-01-02T00:00:00Z".parse().unwrap(),
+                fixed_at: None,
+                dismissed_at: None,
+            },
+        ];
+        schema
+            .db
+            .insert_vulnerability_alerts(alerts, "aicers", "github-dashboard-server")
+            .unwrap();
+
+        let query = r"
+        {
+            vulnerabilityAlerts(first: 1) {
+                edges {
+                    node {
+                        repo
+                        number
+                        package
+                        severity
+                        state
+                        fixedAt
+                    }
+                }
+                pageInfo {
+                    hasNextPage
+                }
+            }
+        }";
+        let data = schema.execute(query).await.data.into_json().unwrap();
+        assert_eq!(
+            data["vulnerabilityAlerts"],
+            serde_json::json!({
+                "edges": [{
+                    "node": {
+                        "repo": "github-dashboard-server",
+                        "number": 1,
+                        "package": "openssl",
+                        "severity": "HIGH",
+                        "state": "FIXED",
+                        "fixedAt": "2025-01-03T00:00:00Z"
+                    }
+                }],
+                "pageInfo": { "hasNextPage": true }
+            })
+        );
+    }
+}
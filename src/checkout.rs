@@ -1,106 +1,707 @@
-use std::time::Duration;
+mod code_size;
+mod codeowners;
+mod commit;
+mod dependency;
+mod ownership;
+mod todo;
+
+use std::time::{Duration, Instant};
 use std::{
     env::var,
     path::{Path, PathBuf},
+    process::Command,
     sync::Arc,
 };
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
 use directories::ProjectDirs;
-use git2::{Cred, FetchOptions, RemoteCallbacks, Repository};
-use tokio::time;
-use tracing::{error, info};
+use git2::{
+    Cred, CredentialType, Direction, ErrorClass, FetchOptions, RemoteCallbacks, Repository,
+    ResetType, Status, StatusOptions,
+};
+use jiff::Timestamp;
+use tokio::{task, time};
+use tracing::{error, info, warn};
 
-use crate::settings::Repository as RepoInfo;
+use crate::{
+    database::{
+        sync_status::{SyncEvent, SyncStatus},
+        Database,
+    },
+    settings::{
+        Certification, Checkout as CheckoutSettings, CloneProtocol, Repository as RepoInfo,
+    },
+};
 
 const FETCH_HEAD: &str = "FETCH_HEAD";
 const LOCAL_BASE_REPO: &str = "repos";
-const MAIN_BRANCH: &str = "main";
+const BRANCH_REF_PREFIX: &str = "refs/heads/";
 const REMOTE_NAME: &str = "origin";
 const REMOTE_BASE_URL: &str = "git@github.com";
+const REMOTE_HTTPS_BASE_URL: &str = "https://github.com";
+/// The user name GitHub expects along with a token for HTTPS authentication.
+const HTTPS_TOKEN_USERNAME: &str = "x-access-token";
 const ENV_HOME: &str = "HOME";
 const ENV_SSH_PASSPHRASE: &str = "SSH_PASSPHRASE";
+const MIB: u64 = 1024 * 1024;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Returns the path of the SSH private key, if one is configured.
+fn ssh_key_path(cert: &Certification) -> Result<Option<PathBuf>> {
+    cert.ssh
+        .as_ref()
+        .map(|ssh| Ok(Path::new(&var(ENV_HOME)?).join(ssh)))
+        .transpose()
+}
 
-fn callbacks(ssh: &str) -> Result<RemoteCallbacks<'_>> {
+/// Returns the callbacks providing the SSH key for SSH remotes and the token for
+/// HTTPS remotes.
+fn callbacks(cert: &Certification) -> Result<RemoteCallbacks<'_>> {
     let mut callbacks = RemoteCallbacks::new();
-    let ssh_path = Path::new(&var(ENV_HOME)?).join(ssh);
+    let ssh_path = ssh_key_path(cert)?;
     let passphrase = std::env::var(ENV_SSH_PASSPHRASE).ok();
-    callbacks.credentials(move |_url, username_from_url, _allowed_types| {
-        Cred::ssh_key(
-            username_from_url.unwrap_or("git"),
-            None,
-            &ssh_path,
-            passphrase.as_deref(),
-        )
+    callbacks.credentials(move |_url, username_from_url, allowed_types| {
+        if allowed_types.contains(CredentialType::SSH_KEY) {
+            if let Some(ssh_path) = &ssh_path {
+                return Cred::ssh_key(
+                    username_from_url.unwrap_or("git"),
+                    None,
+                    ssh_path,
+                    passphrase.as_deref(),
+                );
+            }
+        }
+        if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return Cred::userpass_plaintext(HTTPS_TOKEN_USERNAME, &cert.token);
+        }
+        Err(git2::Error::from_str(
+            "No credentials are configured for the remote",
+        ))
     });
     Ok(callbacks)
 }
 
-fn fetchoption(ssh: &str) -> Result<FetchOptions<'_>> {
+/// Returns the fetch options, limited to `depth` commits if it is set. A clone
+/// made with `since` stays shallow without a depth.
+fn fetchoption<'a>(
+    cert: &'a Certification,
+    checkout: &CheckoutSettings,
+) -> Result<FetchOptions<'a>> {
     let mut fo = git2::FetchOptions::new();
-    fo.remote_callbacks(callbacks(ssh)?);
+    fo.remote_callbacks(callbacks(cert)?);
+    if let (Some(depth), None) = (checkout.depth, checkout.since) {
+        fo.depth(i32::try_from(depth).context("The clone depth is too large")?);
+    }
     Ok(fo)
 }
 
-fn init_repo(repo_owner: &str, repo_name: &str, ssh: &str) -> Result<()> {
-    let mut builder = git2::build::RepoBuilder::new();
-    builder.fetch_options(fetchoption(ssh)?);
-    let path = local_repo_path(repo_name)?;
-    if !path.exists() {
-        std::fs::create_dir_all(&path)?;
-        builder.clone(
-            &format!("{REMOTE_BASE_URL}:{repo_owner}/{repo_name}.git"),
-            &path,
-        )?;
+/// Returns a `git` command authenticated with the same credentials as the
+/// `callbacks`.
+///
+/// An SSH key given this way cannot have a passphrase.
+fn git_command(cert: &Certification) -> Result<Command> {
+    let mut command = Command::new("git");
+    command.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(ssh_path) = ssh_key_path(cert)? {
+        command.env(
+            "GIT_SSH_COMMAND",
+            format!(
+                "ssh -i '{}' -o IdentitiesOnly=yes -o BatchMode=yes",
+                ssh_path.display()
+            ),
+        );
+    }
+    // The token is passed in the environment rather than in the arguments,
+    // which other users can see.
+    let credentials =
+        general_purpose::STANDARD.encode(format!("{HTTPS_TOKEN_USERNAME}:{}", cert.token));
+    command
+        .env("GIT_CONFIG_COUNT", "1")
+        .env(
+            "GIT_CONFIG_KEY_0",
+            format!("http.{REMOTE_HTTPS_BASE_URL}/.extraHeader"),
+        )
+        .env(
+            "GIT_CONFIG_VALUE_0",
+            format!("Authorization: Basic {credentials}"),
+        );
+    Ok(command)
+}
+
+fn run_git(command: &mut Command) -> Result<()> {
+    let output = command.output().context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
     }
     Ok(())
 }
 
-fn local_repo_path(repo_name: &str) -> Result<PathBuf> {
-    if let Some(proj_dirs) = ProjectDirs::from_path(PathBuf::from(LOCAL_BASE_REPO)) {
-        Ok(proj_dirs.cache_dir().join(repo_name))
+/// Returns the URL to clone the repository from.
+///
+/// Unless the repository sets `protocol`, SSH is used if an SSH key is
+/// configured, and HTTPS otherwise.
+fn clone_url(repo_info: &RepoInfo, cert: &Certification) -> String {
+    let protocol = repo_info.protocol.unwrap_or(if cert.ssh.is_some() {
+        CloneProtocol::Ssh
+    } else {
+        CloneProtocol::Https
+    });
+    let (owner, name) = (&repo_info.owner, &repo_info.name);
+    match protocol {
+        CloneProtocol::Ssh => format!("{REMOTE_BASE_URL}:{owner}/{name}.git"),
+        CloneProtocol::Https => format!("{REMOTE_HTTPS_BASE_URL}/{owner}/{name}.git"),
+    }
+}
+
+fn init_repo(
+    repo_info: &RepoInfo,
+    cert: &Certification,
+    checkout: &CheckoutSettings,
+    base: &Path,
+) -> Result<()> {
+    let path = local_repo_path(base, &repo_info.owner, &repo_info.name);
+    if path.exists() {
+        open_repo(base, &repo_info.owner, &repo_info.name)?;
+        return Ok(());
+    }
+    if let Some(max) = checkout.max_disk_usage_mib {
+        if disk_usage(base)? >= max.saturating_mul(MIB) {
+            bail!(
+                "Cannot clone {}/{}: the clones exceed {max} MiB",
+                repo_info.owner,
+                repo_info.name
+            );
+        }
+    }
+    std::fs::create_dir_all(&path)?;
+    let result = if let Some(since) = checkout.since {
+        // libgit2 cannot clone the commits after a date.
+        let mut command = git_command(cert)?;
+        command
+            .args(["clone", "--quiet", "--origin", REMOTE_NAME])
+            .arg(format!("--shallow-since={since}"));
+        if let Some(branch) = &repo_info.branch {
+            command.args(["--branch", branch]);
+        }
+        run_git(command.arg(clone_url(repo_info, cert)).arg(&path))
+    } else {
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(fetchoption(cert, checkout)?);
+        if let Some(branch) = &repo_info.branch {
+            builder.branch(branch);
+        }
+        builder
+            .clone(&clone_url(repo_info, cert), &path)
+            .map(|_| ())
+            .map_err(Into::into)
+    };
+    if result.is_err() {
+        // Leaves no partial clone behind, so that it is cloned again later.
+        std::fs::remove_dir_all(&path)?;
+    }
+    result
+}
+
+/// Opens the local clone, making sure its `origin` is the given repository.
+fn open_repo(base: &Path, repo_owner: &str, repo_name: &str) -> Result<Repository> {
+    let path = local_repo_path(base, repo_owner, repo_name);
+    let repo = Repository::open(&path)?;
+    if !origin_matches(&repo, repo_owner, repo_name)? {
+        bail!(
+            "The origin of {} is not {repo_owner}/{repo_name}",
+            path.display()
+        );
+    }
+    Ok(repo)
+}
+
+fn origin_matches(repo: &Repository, repo_owner: &str, repo_name: &str) -> Result<bool> {
+    let remote = repo.find_remote(REMOTE_NAME)?;
+    Ok(remote
+        .url()
+        .is_some_and(|url| url_matches(url, repo_owner, repo_name)))
+}
+
+/// Returns `true` if `url` points to `repo_owner/repo_name`, in either SSH
+/// (`git@github.com:owner/name.git`) or HTTPS form.
+fn url_matches(url: &str, repo_owner: &str, repo_name: &str) -> bool {
+    let path = url.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut segments = path.rsplit(['/', ':']);
+    let (Some(name), Some(owner)) = (segments.next(), segments.next()) else {
+        return false;
+    };
+    owner.eq_ignore_ascii_case(repo_owner) && name.eq_ignore_ascii_case(repo_name)
+}
+
+/// Moves the clones from the legacy `name` layout to `owner/name`.
+///
+/// A legacy clone is moved only if its `origin` is the configured repository,
+/// so a clone of another owner's repository with the same name is left in
+/// place. Since a legacy clone may sit where an owner directory of the new
+/// layout goes, every clone is moved aside before any is put in place.
+fn migrate_legacy_clones(base: &Path, repositories: &[RepoInfo]) -> Result<()> {
+    let mut moved = Vec::new();
+    for repo_info in repositories {
+        let legacy_path = base.join(&repo_info.name);
+        let path = local_repo_path(base, &repo_info.owner, &repo_info.name);
+        if path.exists() || !legacy_path.join(".git").exists() {
+            continue;
+        }
+        if !origin_matches(
+            &Repository::open(&legacy_path)?,
+            &repo_info.owner,
+            &repo_info.name,
+        )? {
+            continue;
+        }
+        let temp_path = base.join(format!(".{}.migrating", repo_info.name));
+        std::fs::rename(&legacy_path, &temp_path)?;
+        moved.push((legacy_path, temp_path, path));
+    }
+    for (legacy_path, temp_path, path) in moved {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::rename(&temp_path, &path)?;
+        info!("Moved {} to {}", legacy_path.display(), path.display());
+    }
+    Ok(())
+}
+
+/// Returns the name of the branch the remote HEAD points to.
+fn default_branch(repo: &Repository, cert: &Certification) -> Result<String> {
+    let mut remote = repo.find_remote(REMOTE_NAME)?;
+    let mut connection = remote.connect_auth(Direction::Fetch, Some(callbacks(cert)?), None)?;
+    let head = connection.remote().default_branch()?;
+    let head = head.as_str().context("Remote HEAD is not valid UTF-8")?;
+    Ok(head
+        .strip_prefix(BRANCH_REF_PREFIX)
+        .unwrap_or(head)
+        .to_string())
+}
+
+/// Returns the directory the clones are kept in.
+fn local_base_path(checkout: &CheckoutSettings) -> Result<PathBuf> {
+    if let Some(base_dir) = &checkout.base_dir {
+        Ok(base_dir.clone())
+    } else if let Some(proj_dirs) = ProjectDirs::from_path(PathBuf::from(LOCAL_BASE_REPO)) {
+        Ok(proj_dirs.cache_dir().to_path_buf())
     } else {
         Err(anyhow!("Faild to load cache directory"))
     }
 }
 
-fn pull_repo(name: &str, ssh: &str) -> Result<()> {
-    let repo = Repository::open(local_repo_path(name)?)?;
-    repo.find_remote(REMOTE_NAME)?
-        .fetch(&[MAIN_BRANCH], Some(&mut fetchoption(ssh)?), None)?;
+fn local_repo_path(base: &Path, repo_owner: &str, repo_name: &str) -> PathBuf {
+    base.join(repo_owner).join(repo_name)
+}
+
+/// Removes the clones of the repositories that are no longer configured.
+///
+/// Only a directory at `owner/name` whose `origin` is `owner/name` is
+/// removed, so other files in the base directory are left alone.
+fn prune_clones(base: &Path, repositories: &[RepoInfo]) -> Result<()> {
+    if !base.exists() {
+        return Ok(());
+    }
+    for owner_entry in std::fs::read_dir(base)? {
+        let owner_path = owner_entry?.path();
+        let Some(owner) = owner_path.file_name().and_then(|owner| owner.to_str()) else {
+            continue;
+        };
+        if !owner_path.is_dir() || owner_path.join(".git").exists() {
+            continue;
+        }
+        let mut removed = false;
+        for entry in std::fs::read_dir(&owner_path)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if repositories
+                .iter()
+                .any(|repo_info| repo_info.owner == owner && repo_info.name == name)
+            {
+                continue;
+            }
+            let is_clone = Repository::open(&path)
+                .ok()
+                .is_some_and(|repo| origin_matches(&repo, owner, name).unwrap_or(false));
+            if is_clone {
+                std::fs::remove_dir_all(&path)?;
+                removed = true;
+                info!("Removed the clone of {owner}/{name}, which is no longer configured");
+            }
+        }
+        if removed && std::fs::read_dir(&owner_path)?.next().is_none() {
+            std::fs::remove_dir(&owner_path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the total size of the files under `path`, in bytes.
+fn disk_usage(path: &Path) -> Result<u64> {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return Ok(0);
+    };
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        total += disk_usage(&entry?.path())?;
+    }
+    Ok(total)
+}
+
+/// Runs `git gc` on the clones of the repositories.
+fn gc_clones(base: &Path, repositories: &[RepoInfo]) {
+    for repo_info in repositories {
+        let path = local_repo_path(base, &repo_info.owner, &repo_info.name);
+        if !path.exists() {
+            continue;
+        }
+        let mut command = Command::new("git");
+        command.arg("-C").arg(&path).args(["gc", "--quiet"]);
+        if let Err(error) = run_git(&mut command) {
+            error!(
+                "Problem while running git gc on {}/{}. {}",
+                repo_info.owner, repo_info.name, error
+            );
+        }
+    }
+}
+
+/// Updates the local clone to the latest commit of the configured branch, or of
+/// the default branch of the remote if no branch is configured.
+fn pull_repo(
+    repo_info: &RepoInfo,
+    cert: &Certification,
+    checkout: &CheckoutSettings,
+    base: &Path,
+) -> Result<SyncStatus> {
+    let repo = open_repo(base, &repo_info.owner, &repo_info.name)?;
+    let url = clone_url(repo_info, cert);
+    if repo.find_remote(REMOTE_NAME)?.url() != Some(url.as_str()) {
+        repo.remote_set_url(REMOTE_NAME, &url)?;
+    }
+    let branch = match &repo_info.branch {
+        Some(branch) => branch.clone(),
+        None => default_branch(&repo, cert)?,
+    };
+    repo.find_remote(REMOTE_NAME)?.fetch(
+        &[branch.as_str()],
+        Some(&mut fetchoption(cert, checkout)?),
+        None,
+    )?;
     let fetch_head = repo.find_reference(FETCH_HEAD)?;
     let fetch_commit = repo.reference_to_annotated_commit(&fetch_head)?;
-    let (analysis, _) = repo.merge_analysis(&[&fetch_commit])?;
-    if analysis.is_up_to_date() {
-        info!("Already up to date");
-    } else if analysis.is_fast_forward() {
-        let refname = format!("refs/heads/{MAIN_BRANCH}");
-        let mut reference = repo.find_reference(&refname)?;
-        reference.set_target(fetch_commit.id(), "Fast-Forward")?;
+    let refname = format!("{BRANCH_REF_PREFIX}{branch}");
+    let on_branch = repo
+        .head()
+        .is_ok_and(|head| head.name() == Some(refname.as_str()));
+    let event = if on_branch {
+        let (analysis, _) = repo.merge_analysis(&[&fetch_commit])?;
+        if analysis.is_up_to_date() {
+            SyncEvent::UpToDate
+        } else if analysis.is_fast_forward() {
+            let mut reference = repo.find_reference(&refname)?;
+            reference.set_target(fetch_commit.id(), "Fast-Forward")?;
+            repo.set_head(&refname)?;
+            repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
+            SyncEvent::FastForwarded
+        } else {
+            // The upstream branch was force-pushed, or the local branch has
+            // commits of its own.
+            let commit = repo.find_object(fetch_commit.id(), None)?;
+            repo.reset(&commit, ResetType::Hard, None)?;
+            SyncEvent::Reset
+        }
+    } else {
+        repo.reference(&refname, fetch_commit.id(), true, "Switch branch")?;
         repo.set_head(&refname)?;
         repo.checkout_head(Some(git2::build::CheckoutBuilder::default().force()))?;
-        info!("New commit pull success");
+        SyncEvent::BranchSwitched
+    };
+    clean_untracked(&repo)?;
+    sync_status(&repo, event)
+}
+
+/// Removes the untracked files and directories from the working tree.
+fn clean_untracked(repo: &Repository) -> Result<()> {
+    let workdir = repo.workdir().context("The clone has no working tree")?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        if !entry.status().contains(Status::WT_NEW) {
+            continue;
+        }
+        let Some(path) = entry.path() else {
+            continue;
+        };
+        let path = workdir.join(path);
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
     }
     Ok(())
 }
 
+fn sync_status(repo: &Repository, event: SyncEvent) -> Result<SyncStatus> {
+    let head = repo.head()?;
+    let branch = head
+        .shorthand()
+        .context("The branch name is not valid UTF-8")?
+        .to_string();
+    let head = head.peel_to_commit()?.id().to_string();
+    Ok(SyncStatus {
+        synced_at: Timestamp::now(),
+        branch,
+        head,
+        event,
+    })
+}
+
+/// Returns `true` if the error comes from reading the objects or the index of
+/// the local clone, rather than from the remote or the references.
+fn is_storage_error(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<git2::Error>().is_some_and(|error| {
+        matches!(
+            error.class(),
+            ErrorClass::Object | ErrorClass::Odb | ErrorClass::Index | ErrorClass::Zlib
+        )
+    })
+}
+
+/// Returns `true` if the objects or the index of the local clone cannot be
+/// read.
+fn is_corrupted(base: &Path, owner: &str, name: &str) -> bool {
+    Repository::open(local_repo_path(base, owner, name))
+        .map_or(true, |repo| check_integrity(&repo).is_err())
+}
+
+/// Reads the index and every object of the repository.
+fn check_integrity(repo: &Repository) -> Result<()> {
+    repo.index()?.read(true)?;
+    let odb = repo.odb()?;
+    let mut broken = Ok(());
+    let walk = odb.foreach(|oid| match odb.read(*oid) {
+        Ok(_) => true,
+        Err(error) => {
+            broken = Err(error);
+            false
+        }
+    });
+    broken?;
+    walk?;
+    Ok(())
+}
+
+/// Pulls the repository, cloning it again if the local clone is corrupted.
+///
+/// Errors of the references, such as a configured branch missing on the
+/// remote, are returned as they are, since cloning again would not fix them.
+fn sync_repo(
+    repo_info: &RepoInfo,
+    cert: &Certification,
+    checkout: &CheckoutSettings,
+    base: &Path,
+) -> Result<SyncStatus> {
+    let (owner, name) = (&repo_info.owner, &repo_info.name);
+    match pull_repo(repo_info, cert, checkout, base) {
+        Err(error) if is_storage_error(&error) && is_corrupted(base, owner, name) => {
+            warn!("The clone of {owner}/{name} is corrupted and is cloned again. {error}");
+            let path = local_repo_path(base, owner, name);
+            if path.exists() {
+                std::fs::remove_dir_all(&path)?;
+            }
+            init_repo(repo_info, cert, checkout, base)?;
+            sync_status(&open_repo(base, owner, name)?, SyncEvent::Recloned)
+        }
+        result => result,
+    }
+}
+
+/// Reads the synced clone and stores what is derived from its history.
+fn analyze_repo(owner: &str, name: &str, base: &Path, db: &Database) -> Result<()> {
+    let repo = open_repo(base, owner, name)?;
+    let count = commit::ingest_commits(&repo, owner, name, db)?;
+    info!("Stored {count} new commits of {owner}/{name}");
+    db.insert_code_size_snapshot(code_size::scan(&repo)?, owner, name)?;
+    db.insert_codeowners(codeowners::read(&repo)?, owner, name)?;
+    db.insert_dependencies(dependency::scan(&repo)?, owner, name)?;
+    db.insert_code_todos(todo::scan(&repo, owner, name)?, owner, name)?;
+    let count = ownership::update_ownership(&repo, owner, name, db)?;
+    info!("Updated the ownership of {count} files of {owner}/{name}");
+    Ok(())
+}
+
 pub(super) async fn fetch_periodically(
     repositories: Arc<Vec<RepoInfo>>,
     duration: Duration,
-    ssh: String,
+    cert: Certification,
+    checkout: CheckoutSettings,
+    db: Database,
 ) {
+    let base = match local_base_path(&checkout) {
+        Ok(base) => base,
+        Err(error) => {
+            error!("{}", error);
+            return;
+        }
+    };
+    if let Err(error) = migrate_legacy_clones(&base, &repositories) {
+        error!("Problem while moving legacy clones. {}", error);
+    }
+    if let Err(error) = prune_clones(&base, &repositories) {
+        error!("Problem while removing unused clones. {}", error);
+    }
     for repo_info in repositories.iter() {
-        if let Err(error) = init_repo(&repo_info.owner, &repo_info.name, &ssh) {
+        if let Err(error) = init_repo(repo_info, &cert, &checkout, &base) {
             error!("{}", error);
         }
     }
+    let gc_interval = checkout
+        .gc_interval_days
+        .map(|days| Duration::from_secs(days * SECONDS_PER_DAY));
+    let mut last_gc = Instant::now();
     let mut itv = time::interval(duration);
     loop {
         itv.tick().await;
         for repo_info in repositories.iter() {
-            if let Err(error) = pull_repo(&repo_info.name, &ssh) {
-                error!("Problem while git pull. {}", error);
+            let path = local_repo_path(&base, &repo_info.owner, &repo_info.name);
+            if !path.exists() {
+                // The clone failed before, possibly for lack of disk space.
+                if let Err(error) = init_repo(repo_info, &cert, &checkout, &base) {
+                    error!("{}", error);
+                    continue;
+                }
+            }
+            match sync_repo(repo_info, &cert, &checkout, &base) {
+                Ok(status) => {
+                    info!(
+                        "Synced {}/{} ({:?})",
+                        repo_info.owner, repo_info.name, status.event
+                    );
+                    if let Err(error) =
+                        db.insert_sync_status(status, &repo_info.owner, &repo_info.name)
+                    {
+                        error!("Problem while insert Fjall Database. {}", error);
+                    }
+                    // The analysis walks the history and the whole tree, so it
+                    // runs off the async runtime.
+                    let (owner, name) = (repo_info.owner.clone(), repo_info.name.clone());
+                    let (base, db) = (base.clone(), db.clone());
+                    match task::spawn_blocking(move || analyze_repo(&owner, &name, &base, &db))
+                        .await
+                    {
+                        Ok(Ok(())) => {}
+                        Ok(Err(error)) => {
+                            error!("Problem while analyzing the clone. {}", error);
+                        }
+                        Err(error) => error!("The analysis of the clone panicked. {}", error),
+                    }
+                }
+                Err(error) => error!("Problem while git pull. {}", error),
+            }
+        }
+        if gc_interval.is_some_and(|interval| last_gc.elapsed() >= interval) {
+            gc_clones(&base, &repositories);
+            last_gc = Instant::now();
+        }
+        if let Some(max) = checkout.max_disk_usage_mib {
+            match disk_usage(&base) {
+                Ok(usage) if usage > max.saturating_mul(MIB) => {
+                    gc_clones(&base, &repositories);
+                    last_gc = Instant::now();
+                    if disk_usage(&base).is_ok_and(|usage| usage > max.saturating_mul(MIB)) {
+                        warn!("The clones exceed {max} MiB even after git gc");
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => error!("Problem while measuring the clones. {}", error),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use git2::Repository;
+
+    use super::{is_corrupted, is_storage_error, prune_clones, url_matches};
+    use crate::settings::Repository as RepoInfo;
+
+    #[test]
+    fn prune_unconfigured_clones() {
+        let base = tempfile::tempdir().unwrap();
+        for (owner, name) in [("aicers", "kept"), ("aicers", "removed")] {
+            let repo = Repository::init(base.path().join(owner).join(name)).unwrap();
+            repo.remote("origin", &format!("git@github.com:{owner}/{name}.git"))
+                .unwrap();
+        }
+        std::fs::create_dir_all(base.path().join("notes").join("drafts")).unwrap();
+        let repositories = [RepoInfo {
+            owner: "aicers".to_string(),
+            name: "kept".to_string(),
+            branch: None,
+            protocol: None,
+        }];
+
+        prune_clones(base.path(), &repositories).unwrap();
+        assert!(base.path().join("aicers").join("kept").exists());
+        assert!(!base.path().join("aicers").join("removed").exists());
+        assert!(base.path().join("notes").join("drafts").exists());
+    }
+
+    #[test]
+    fn url_matches_ssh_and_https() {
+        assert!(url_matches(
+            "git@github.com:aicers/docs.git",
+            "aicers",
+            "docs"
+        ));
+        assert!(url_matches(
+            "https://github.com/Aicers/docs",
+            "aicers",
+            "docs"
+        ));
+        assert!(!url_matches(
+            "git@github.com:other-org/docs.git",
+            "aicers",
+            "docs"
+        ));
+        assert!(!url_matches("docs", "aicers", "docs"));
+    }
+
+    #[test]
+    fn reclone_only_damaged_objects() {
+        let base = tempfile::tempdir().unwrap();
+        let repo = Repository::init(base.path().join("aicers").join("docs")).unwrap();
+        let blob = repo.blob(b"content").unwrap();
+        assert!(!is_corrupted(base.path(), "aicers", "docs"));
+
+        // A missing branch is not a reason to clone again.
+        let error = repo
+            .find_reference("refs/heads/missing")
+            .map(|_| ())
+            .unwrap_err();
+        assert!(!is_storage_error(&error.into()));
+
+        let hex = blob.to_string();
+        let object = repo.path().join("objects").join(&hex[..2]).join(&hex[2..]);
+        let mut permissions = std::fs::metadata(&object).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&object, permissions).unwrap();
+        std::fs::write(&object, b"damaged").unwrap();
+        assert!(is_corrupted(base.path(), "aicers", "docs"));
+    }
+}
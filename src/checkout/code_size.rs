@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::Repository;
+use jiff::Timestamp;
+
+use crate::database::code_size::{CodeSizeSnapshot, LanguageLineCount};
+
+struct Language {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+const LANGUAGES: &[Language] = &[
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "Go",
+        extensions: &["go"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "C",
+        extensions: &["c", "h"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "C++",
+        extensions: &["cc", "cpp", "cxx", "hpp", "hh"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "JavaScript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "TypeScript",
+        extensions: &["ts", "tsx"],
+        line_comments: &["//"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "CSS",
+        extensions: &["css", "scss"],
+        line_comments: &[],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "HTML",
+        extensions: &["html", "htm"],
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+    },
+    Language {
+        name: "Python",
+        extensions: &["py"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "Shell",
+        extensions: &["sh", "bash"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "SQL",
+        extensions: &["sql"],
+        line_comments: &["--"],
+        block_comment: C_BLOCK,
+    },
+    Language {
+        name: "GraphQL",
+        extensions: &["graphql", "gql"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "TOML",
+        extensions: &["toml"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "YAML",
+        extensions: &["yml", "yaml"],
+        line_comments: &["#"],
+        block_comment: None,
+    },
+    Language {
+        name: "JSON",
+        extensions: &["json"],
+        line_comments: &[],
+        block_comment: None,
+    },
+    Language {
+        name: "Markdown",
+        extensions: &["md"],
+        line_comments: &[],
+        block_comment: Some(("<!--", "-->")),
+    },
+];
+
+fn language(path: &Path) -> Option<&'static Language> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|language| language.extensions.contains(&extension.as_str()))
+}
+
+/// Counts the code, comment and blank lines of `source`.
+///
+/// A line is a comment only if it holds nothing but a comment; a line with
+/// code followed by a trailing comment is counted as code.
+fn count_lines(language: &Language, source: &str) -> (usize, usize, usize) {
+    let (mut code, mut comment, mut blank) = (0, 0, 0);
+    let mut in_block = false;
+    for line in source.lines() {
+        let line = line.trim();
+        if in_block {
+            comment += 1;
+            if let Some((_, end)) = language.block_comment {
+                in_block = !line.contains(end);
+            }
+        } else if line.is_empty() {
+            blank += 1;
+        } else if language
+            .line_comments
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            comment += 1;
+        } else if let Some((rest, end)) = language
+            .block_comment
+            .and_then(|(start, end)| Some((line.strip_prefix(start)?, end)))
+        {
+            match rest.find(end) {
+                Some(pos) if !rest[pos + end.len()..].trim().is_empty() => code += 1,
+                Some(_) => comment += 1,
+                None => {
+                    comment += 1;
+                    in_block = true;
+                }
+            }
+        } else {
+            code += 1;
+        }
+    }
+    (code, comment, blank)
+}
+
+/// Counts the lines of the tracked files in the working tree, per language.
+pub(super) fn scan(repo: &Repository) -> Result<CodeSizeSnapshot> {
+    let workdir = repo.workdir().context("The clone has no working tree")?;
+    let mut counts: BTreeMap<&str, LanguageLineCount> = BTreeMap::new();
+    for entry in repo.index()?.iter() {
+        let path = Path::new(std::str::from_utf8(&entry.path)?);
+        let Some(language) = language(path) else {
+            continue;
+        };
+        // Files that are not UTF-8 text are skipped.
+        let Ok(source) = std::fs::read_to_string(workdir.join(path)) else {
+            continue;
+        };
+        let (code, comment, blank) = count_lines(language, &source);
+        let count = counts
+            .entry(language.name)
+            .or_insert_with(|| LanguageLineCount {
+                language: language.name.to_string(),
+                ..Default::default()
+            });
+        count.files += 1;
+        count.code += code;
+        count.comment += comment;
+        count.blank += blank;
+    }
+    Ok(CodeSizeSnapshot {
+        taken_at: Timestamp::now(),
+        languages: counts.into_values().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_lines, LANGUAGES};
+
+    #[test]
+    fn count_rust_lines() {
+        let rust = &LANGUAGES[0];
+        let source = "\
+//! Crate docs.
+
+/* A block
+   comment */
+fn main() {
+    /* inline */ let x = 1; // trailing
+
+}
+";
+        assert_eq!(count_lines(rust, source), (3, 3, 2));
+    }
+}
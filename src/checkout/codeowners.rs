@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{ObjectType, Repository};
+
+use crate::database::codeowners::CodeownerRule;
+
+/// The locations GitHub looks for the `CODEOWNERS` file in, in order.
+const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Reads the rules of the `CODEOWNERS` file at the head of the checked-out
+/// branch. Returns no rules if the repository has no such file.
+pub(super) fn read(repo: &Repository) -> Result<Vec<CodeownerRule>> {
+    let tree = repo.head()?.peel_to_tree()?;
+    for path in CODEOWNERS_PATHS {
+        let Ok(entry) = tree.get_path(Path::new(path)) else {
+            continue;
+        };
+        if entry.kind() != Some(ObjectType::Blob) {
+            continue;
+        }
+        let blob = entry
+            .to_object(repo)?
+            .peel_to_blob()
+            .with_context(|| format!("Failed to read {path}"))?;
+        return Ok(parse(&String::from_utf8_lossy(blob.content())));
+    }
+    Ok(Vec::new())
+}
+
+/// Parses the contents of a `CODEOWNERS` file, keeping the order of the rules.
+fn parse(contents: &str) -> Vec<CodeownerRule> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line
+                .split_whitespace()
+                .take_while(|token| !token.starts_with('#'));
+            let pattern = tokens.next()?;
+            Some(CodeownerRule {
+                pattern: pattern.to_string(),
+                owners: tokens.map(ToString::to_string).collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+    use crate::database::codeowners::CodeownerRule;
+
+    #[test]
+    fn parse_rules() {
+        let contents = "\
+# Default owners
+*       @aicers/core
+
+/docs/  @alice docs@example.com # Documentation
+/vendor/
+";
+        assert_eq!(
+            parse(contents),
+            vec![
+                CodeownerRule {
+                    pattern: "*".to_string(),
+                    owners: vec!["@aicers/core".to_string()],
+                },
+                CodeownerRule {
+                    pattern: "/docs/".to_string(),
+                    owners: vec!["@alice".to_string(), "docs@example.com".to_string()],
+                },
+                CodeownerRule {
+                    pattern: "/vendor/".to_string(),
+                    owners: vec![],
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use std::{collections::HashSet, fs, io};
+
+use anyhow::{Context, Result};
+use git2::{Commit, Diff, Oid, Patch, Repository, Sort};
+use jiff::Timestamp;
+
+use crate::database::{
+    commit::{GitCommit, GitFileChange},
+    commit_link::parse_commit_links,
+    Database,
+};
+
+/// Stores the commits reachable from HEAD that have not been stored yet.
+pub(super) fn ingest_commits(
+    repo: &Repository,
+    owner: &str,
+    name: &str,
+    db: &Database,
+) -> Result<usize> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(head)?;
+    if let Some(stored_head) = db.commit_head(owner, name) {
+        let stored_head = Oid::from_str(&stored_head)
+            .ok()
+            .filter(|&oid| oid == head || repo.graph_descendant_of(head, oid).unwrap_or(false));
+        if let Some(oid) = stored_head {
+            revwalk.hide(oid)?;
+        } else {
+            // The history was rewritten by a force-push, so the stored
+            // commits may no longer be part of it. The whole history is
+            // walked again.
+            db.remove_repository_commits(owner, name)?;
+        }
+    }
+    if !db.commit_links_indexed(owner, name) {
+        // Links the commits stored before commits were linked to issues.
+        for commit in db.repository_commits(owner, name) {
+            let commit = commit?;
+            let links = parse_commit_links(&commit.message, owner, name);
+            db.insert_commit_links(links, owner, name, &commit.oid)?;
+        }
+        db.insert_commit_links_indexed(owner, name)?;
+    }
+    let boundary = shallow_boundary(repo)?;
+    let commits = revwalk
+        .map(|oid| {
+            let commit = repo.find_commit(oid?)?;
+            git_commit(repo, &commit, boundary.contains(&commit.id()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for commit in &commits {
+        let links = parse_commit_links(&commit.message, owner, name);
+        db.insert_commit_links(links, owner, name, &commit.oid)?;
+    }
+    let count = commits.len();
+    db.insert_commits(commits, owner, name)?;
+    db.insert_commit_head(owner, name, &head.to_string())?;
+    Ok(count)
+}
+
+/// Returns the commits at the boundary of a shallow clone, whose parents were
+/// not fetched.
+fn shallow_boundary(repo: &Repository) -> Result<HashSet<Oid>> {
+    let contents = match fs::read_to_string(repo.path().join("shallow")) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(HashSet::new()),
+        Err(error) => return Err(error.into()),
+    };
+    contents
+        .lines()
+        .map(|line| Ok(Oid::from_str(line.trim())?))
+        .collect()
+}
+
+/// Reads a commit. The file changes of a commit at the boundary of a shallow
+/// clone are left out, as diffing it against an empty tree would count every
+/// file as added.
+fn git_commit(repo: &Repository, commit: &Commit, is_boundary: bool) -> Result<GitCommit> {
+    let author = commit.author();
+    let committer = commit.committer();
+    Ok(GitCommit {
+        oid: commit.id().to_string(),
+        author_name: author.name().unwrap_or_default().to_string(),
+        author_email: author.email().unwrap_or_default().to_string(),
+        authored_at: Timestamp::from_second(author.when().seconds())?,
+        committer_name: committer.name().unwrap_or_default().to_string(),
+        committer_email: committer.email().unwrap_or_default().to_string(),
+        committed_at: Timestamp::from_second(committer.when().seconds())?,
+        message: commit.message().unwrap_or_default().to_string(),
+        parent_count: commit.parent_count(),
+        files: if is_boundary {
+            Vec::new()
+        } else {
+            file_changes(&commit_diff(repo, commit)?)?
+        },
+    })
+}
+
+/// Returns the diff of the commit against its first parent.
+pub(super) fn commit_diff<'a>(repo: &'a Repository, commit: &Commit) -> Result<Diff<'a>> {
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?)
+}
+
+fn file_changes(diff: &Diff) -> Result<Vec<GitFileChange>> {
+    (0..diff.deltas().len())
+        .filter_map(|idx| Patch::from_diff(diff, idx).transpose())
+        .map(|patch| {
+            let patch = patch?;
+            let delta = patch.delta();
+            let file_path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .context("The diff has no path")?
+                .to_string_lossy()
+                .into_owned();
+            let (_, additions, deletions) = patch.line_stats()?;
+            Ok(GitFileChange {
+                path: file_path,
+                additions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use git2::{Oid, Repository, Signature, Time};
+
+    use super::ingest_commits;
+    use crate::database::Database;
+
+    /// Commits an empty tree on top of `parent` and moves `main` to it.
+    fn commit(repo: &Repository, message: &str, parent: Option<Oid>) -> Oid {
+        let signature = Signature::new("alice", "alice@example.com", &Time::new(0, 0)).unwrap();
+        let tree = repo
+            .find_tree(repo.treebuilder(None).unwrap().write().unwrap())
+            .unwrap();
+        let parents: Vec<_> = parent
+            .map(|oid| repo.find_commit(oid).unwrap())
+            .into_iter()
+            .collect();
+        let parents: Vec<_> = parents.iter().collect();
+        let oid = repo
+            .commit(None, &signature, &signature, message, &tree, &parents)
+            .unwrap();
+        repo.reference("refs/heads/main", oid, true, message)
+            .unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        oid
+    }
+
+    fn stored_oids(db: &Database) -> Vec<String> {
+        let mut oids: Vec<_> = db
+            .commits(None, None)
+            .map(|commit| commit.unwrap().oid)
+            .collect();
+        oids.sort();
+        oids
+    }
+
+    #[test]
+    fn forget_commits_after_force_push() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(&dir.path().join("db")).unwrap();
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        let first = commit(&repo, "First", None);
+        let dropped = commit(&repo, "Dropped\n\nFixes #3", Some(first));
+        assert_eq!(ingest_commits(&repo, "aicers", "frontary", &db).unwrap(), 2);
+
+        let added = commit(&repo, "Added", Some(dropped));
+        assert_eq!(ingest_commits(&repo, "aicers", "frontary", &db).unwrap(), 1);
+
+        let rewritten = commit(&repo, "Rewritten", Some(first));
+        assert_eq!(ingest_commits(&repo, "aicers", "frontary", &db).unwrap(), 2);
+        let mut expected = vec![first.to_string(), rewritten.to_string()];
+        expected.sort();
+        assert_eq!(stored_oids(&db), expected);
+        assert!(!stored_oids(&db).contains(&added.to_string()));
+        assert!(db
+            .linked_commits("aicers", "frontary", 3)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn skip_file_changes_of_shallow_boundary() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(&dir.path().join("db")).unwrap();
+        let repo = Repository::init(dir.path().join("repo")).unwrap();
+        let signature = Signature::new("alice", "alice@example.com", &Time::new(0, 0)).unwrap();
+        let mut builder = repo.treebuilder(None).unwrap();
+        builder
+            .insert("main.rs", repo.blob(b"fn main() {}\n").unwrap(), 0o100_644)
+            .unwrap();
+        let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+        let oid = repo
+            .commit(Some("HEAD"), &signature, &signature, "Add main", &tree, &[])
+            .unwrap();
+        fs::write(repo.path().join("shallow"), format!("{oid}\n")).unwrap();
+
+        assert_eq!(ingest_commits(&repo, "aicers", "frontary", &db).unwrap(), 1);
+        let commit = db
+            .commit("aicers", "frontary", &oid.to_string())
+            .unwrap()
+            .unwrap();
+        assert!(commit.files.is_empty());
+    }
+}
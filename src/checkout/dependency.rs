@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use toml::{Table, Value};
+
+use crate::database::dependency::{CargoDependency, DependencyKind};
+
+const MANIFEST: &str = "Cargo.toml";
+const LOCKFILE: &str = "Cargo.lock";
+
+/// The dependency tables of a manifest and the kind of their dependencies.
+const DEPENDENCY_TABLES: &[(&str, DependencyKind)] = &[
+    ("dependencies", DependencyKind::Normal),
+    ("dev-dependencies", DependencyKind::Dev),
+    ("build-dependencies", DependencyKind::Build),
+];
+
+/// Reads the dependencies declared in every `Cargo.toml` at the head of the
+/// checked-out branch, with the versions locked in the nearest `Cargo.lock`.
+pub(super) fn scan(repo: &Repository) -> Result<Vec<CargoDependency>> {
+    let tree = repo.head()?.peel_to_tree()?;
+    let mut manifests = Vec::new();
+    let mut lockfiles = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            match entry.name() {
+                Some(MANIFEST) => manifests.push(dir.to_string()),
+                Some(LOCKFILE) => lockfiles.push(dir.to_string()),
+                _ => {}
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let read = |path: &str| -> Result<Option<String>> {
+        let entry = tree.get_path(Path::new(path))?;
+        let blob = entry
+            .to_object(repo)?
+            .peel_to_blob()
+            .with_context(|| format!("Failed to read {path}"))?;
+        Ok(std::str::from_utf8(blob.content()).ok().map(str::to_string))
+    };
+    let mut locked = HashMap::new();
+    for dir in lockfiles {
+        if let Some(contents) = read(&format!("{dir}{LOCKFILE}"))? {
+            locked.insert(dir, parse_lockfile(&contents));
+        }
+    }
+    let workspace = read(MANIFEST)
+        .ok()
+        .flatten()
+        .and_then(|contents| contents.parse::<Table>().ok())
+        .and_then(|mut manifest| manifest.remove("workspace"))
+        .and_then(|workspace| match workspace {
+            Value::Table(mut workspace) => workspace.remove("dependencies"),
+            _ => None,
+        })
+        .and_then(|dependencies| match dependencies {
+            Value::Table(dependencies) => Some(dependencies),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let mut dependencies = Vec::new();
+    for dir in manifests {
+        let path = format!("{dir}{MANIFEST}");
+        let Some(contents) = read(&path)? else {
+            continue;
+        };
+        // A manifest that fails to parse is skipped rather than failing the
+        // whole scan.
+        let Ok(manifest) = contents.parse::<Table>() else {
+            continue;
+        };
+        let lockfile = nearest_lockfile(&dir, &locked);
+        for mut dependency in parse_manifest(&manifest, &workspace) {
+            dependency.manifest.clone_from(&path);
+            if let Some(versions) = lockfile.and_then(|lockfile| lockfile.get(&dependency.name)) {
+                dependency.resolved = resolve(dependency.requirement.as_deref(), versions);
+            }
+            dependencies.push(dependency);
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Returns the locked versions of the `Cargo.lock` in `dir` or its closest
+/// ancestor.
+fn nearest_lockfile<'a>(
+    dir: &str,
+    locked: &'a HashMap<String, HashMap<String, Vec<String>>>,
+) -> Option<&'a HashMap<String, Vec<String>>> {
+    let mut dir = dir;
+    loop {
+        if let Some(lockfile) = locked.get(dir) {
+            return Some(lockfile);
+        }
+        if dir.is_empty() {
+            return None;
+        }
+        // Directories from the tree walk end with a slash.
+        dir = dir
+            .trim_end_matches('/')
+            .rsplit_once('/')
+            .map_or("", |(parent, _)| &dir[..=parent.len()]);
+    }
+}
+
+/// Returns the versions of each package locked in a `Cargo.lock`.
+fn parse_lockfile(contents: &str) -> HashMap<String, Vec<String>> {
+    let mut versions: HashMap<String, Vec<String>> = HashMap::new();
+    let Ok(lockfile) = contents.parse::<Table>() else {
+        return versions;
+    };
+    let packages = lockfile.get("package").and_then(Value::as_array);
+    for package in packages.into_iter().flatten() {
+        let name = package.get("name").and_then(Value::as_str);
+        let version = package.get("version").and_then(Value::as_str);
+        if let (Some(name), Some(version)) = (name, version) {
+            versions
+                .entry(name.to_string())
+                .or_default()
+                .push(version.to_string());
+        }
+    }
+    versions
+}
+
+/// Returns the dependencies declared in a manifest, including the ones
+/// specific to a target. Dependencies given only by a path are skipped, as
+/// they are part of the repository.
+fn parse_manifest(manifest: &Table, workspace: &Table) -> Vec<CargoDependency> {
+    let targets = manifest
+        .get("target")
+        .and_then(Value::as_table)
+        .into_iter()
+        .flat_map(|targets| targets.values().filter_map(Value::as_table));
+    let mut dependencies = Vec::new();
+    for table in std::iter::once(manifest).chain(targets) {
+        for (key, kind) in DEPENDENCY_TABLES {
+            let Some(entries) = table.get(*key).and_then(Value::as_table) else {
+                continue;
+            };
+            for (name, spec) in entries {
+                if let Some(dependency) = parse_dependency(name, spec, *kind, workspace) {
+                    dependencies.push(dependency);
+                }
+            }
+        }
+    }
+    dependencies
+}
+
+fn parse_dependency(
+    name: &str,
+    spec: &Value,
+    kind: DependencyKind,
+    workspace: &Table,
+) -> Option<CargoDependency> {
+    let mut dependency = CargoDependency {
+        name: name.to_string(),
+        kind,
+        ..Default::default()
+    };
+    match spec {
+        Value::String(requirement) => dependency.requirement = Some(requirement.clone()),
+        Value::Table(spec) => {
+            if spec.get("workspace").and_then(Value::as_bool) == Some(true) {
+                let inherited = parse_dependency(name, workspace.get(name)?, kind, &Table::new())?;
+                dependency.name = inherited.name;
+                dependency.requirement = inherited.requirement;
+                dependency.features = inherited.features;
+            } else if spec.contains_key("path") && !spec.contains_key("version") {
+                return None;
+            }
+            if let Some(package) = spec.get("package").and_then(Value::as_str) {
+                package.clone_into(&mut dependency.name);
+            }
+            if let Some(requirement) = spec.get("version").and_then(Value::as_str) {
+                dependency.requirement = Some(requirement.to_string());
+            }
+            let features = spec.get("features").and_then(Value::as_array);
+            dependency.features.extend(
+                features
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string),
+            );
+            dependency.features.sort_unstable();
+            dependency.features.dedup();
+        }
+        _ => return None,
+    }
+    Some(dependency)
+}
+
+/// Returns the numeric components of a version, ignoring any pre-release or
+/// build metadata.
+fn numeric(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.trim().parse().unwrap_or_default())
+        .collect()
+}
+
+/// Returns whether `version` satisfies `requirement` as a caret requirement,
+/// which is what Cargo assumes for a bare version. Requirements with other
+/// operators are considered satisfied.
+fn satisfies(requirement: &str, version: &str) -> bool {
+    let requirement = requirement.split(',').next().unwrap_or_default().trim();
+    if requirement.starts_with(['>', '<', '*']) {
+        return true;
+    }
+    let required = numeric(requirement.trim_start_matches(['^', '~', '=', ' ']));
+    let version = numeric(version);
+    let significant = required
+        .iter()
+        .position(|part| *part != 0)
+        .map_or(required.len(), |pos| pos + 1);
+    version.len() >= significant
+        && version[..significant] == required[..significant]
+        && version >= required
+}
+
+/// Picks the locked version a requirement resolved to. If several versions
+/// of the crate are locked, the highest one satisfying the requirement wins.
+fn resolve(requirement: Option<&str>, versions: &[String]) -> Option<String> {
+    if let [version] = versions {
+        return Some(version.clone());
+    }
+    versions
+        .iter()
+        .filter(|version| requirement.is_none_or(|requirement| satisfies(requirement, version)))
+        .max_by_key(|version| numeric(version))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use toml::Table;
+
+    use super::{nearest_lockfile, parse_lockfile, parse_manifest, resolve};
+    use crate::database::dependency::{CargoDependency, DependencyKind};
+
+    #[test]
+    fn parse_dependencies() {
+        let workspace: Table = r#"
+            serde = { version = "1.0.200", features = ["derive"] }
+        "#
+        .parse()
+        .unwrap();
+        let manifest: Table = r#"
+            [dependencies]
+            anyhow = "1"
+            serde = { workspace = true }
+            tokio = { version = "1.40", features = ["rt", "macros"] }
+            utils = { path = "../utils" }
+            git = { package = "git2", version = "0.20" }
+
+            [target.'cfg(unix)'.dev-dependencies]
+            tempfile = "3"
+        "#
+        .parse()
+        .unwrap();
+        let dependency = |name: &str, kind, requirement: &str, features: &[&str]| CargoDependency {
+            name: name.to_string(),
+            kind,
+            requirement: Some(requirement.to_string()),
+            features: features.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_manifest(&manifest, &workspace),
+            vec![
+                dependency("anyhow", DependencyKind::Normal, "1", &[]),
+                dependency("git2", DependencyKind::Normal, "0.20", &[]),
+                dependency("serde", DependencyKind::Normal, "1.0.200", &["derive"]),
+                dependency("tokio", DependencyKind::Normal, "1.40", &["macros", "rt"]),
+                dependency("tempfile", DependencyKind::Dev, "3", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_locked_versions() {
+        let lockfile = parse_lockfile(
+            r#"
+            version = 4
+
+            [[package]]
+            name = "syn"
+            version = "1.0.109"
+
+            [[package]]
+            name = "syn"
+            version = "2.0.87"
+
+            [[package]]
+            name = "zerocopy"
+            version = "0.7.35"
+            "#,
+        );
+        assert_eq!(
+            resolve(Some("1"), &lockfile["syn"]),
+            Some("1.0.109".to_string())
+        );
+        assert_eq!(
+            resolve(Some("2.0"), &lockfile["syn"]),
+            Some("2.0.87".to_string())
+        );
+        assert_eq!(
+            resolve(Some("0.7"), &lockfile["zerocopy"]),
+            Some("0.7.35".to_string())
+        );
+        assert_eq!(resolve(Some("3"), &lockfile["syn"]), None);
+
+        let locked = HashMap::from([(String::new(), lockfile)]);
+        assert!(nearest_lockfile("crates/core/", &locked).is_some());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{
+    BlameOptions, Delta, FileMode, ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult,
+};
+use tracing::warn;
+
+use crate::database::{
+    ownership::{AuthorLines, FileOwnership},
+    Database,
+};
+
+/// Blames the files changed since the ownership was last computed, and stores
+/// the number of lines of each author per file. Returns the number of files
+/// blamed.
+pub(super) fn update_ownership(
+    repo: &Repository,
+    owner: &str,
+    name: &str,
+    db: &Database,
+) -> Result<usize> {
+    let head = repo.head()?.peel_to_commit()?;
+    let previous = db
+        .ownership_head(owner, name)
+        .and_then(|oid| Oid::from_str(&oid).ok())
+        .and_then(|oid| repo.find_commit(oid).ok());
+    let paths = if let Some(previous) = previous {
+        let diff = repo.diff_tree_to_tree(Some(&previous.tree()?), Some(&head.tree()?), None)?;
+        let mut paths = Vec::new();
+        for delta in diff.deltas() {
+            if let Some(old_path) = delta.old_file().path() {
+                if matches!(delta.status(), Delta::Deleted | Delta::Renamed) {
+                    db.remove_file_ownership(owner, name, &path_str(old_path)?)?;
+                }
+            }
+            // Submodules have no lines to blame.
+            if delta.status() != Delta::Deleted && delta.new_file().mode() != FileMode::Commit {
+                if let Some(new_path) = delta.new_file().path() {
+                    paths.push(path_str(new_path)?);
+                }
+            }
+        }
+        paths
+    } else {
+        db.clear_ownership(owner, name)?;
+        tracked_files(&head.tree()?)?
+    };
+
+    let mut options = BlameOptions::new();
+    options.newest_commit(head.id());
+    let mut count = 0;
+    for path in &paths {
+        // A file that cannot be blamed is skipped rather than failing the
+        // update, which would blame every changed file again next time.
+        let blame = match repo.blame_file(Path::new(path), Some(&mut options)) {
+            Ok(blame) => blame,
+            Err(error) => {
+                warn!("Problem while blaming {path} of {owner}/{name}. {error}");
+                continue;
+            }
+        };
+        let mut lines: HashMap<String, usize> = HashMap::new();
+        for hunk in blame.iter() {
+            let author = hunk
+                .final_signature()
+                .name()
+                .unwrap_or_default()
+                .to_string();
+            *lines.entry(author).or_default() += hunk.lines_in_hunk();
+        }
+        let mut authors: Vec<AuthorLines> = lines
+            .into_iter()
+            .map(|(author, lines)| AuthorLines { author, lines })
+            .collect();
+        authors.sort_by(|a, b| b.lines.cmp(&a.lines).then(a.author.cmp(&b.author)));
+        db.insert_file_ownership(
+            FileOwnership {
+                path: path.clone(),
+                authors,
+            },
+            owner,
+            name,
+        )?;
+        count += 1;
+    }
+    db.insert_ownership_head(owner, name, &head.id().to_string())?;
+    Ok(count)
+}
+
+fn tracked_files(tree: &git2::Tree) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                paths.push(format!("{dir}{name}"));
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(paths)
+}
+
+fn path_str(path: &Path) -> Result<String> {
+    Ok(path
+        .to_str()
+        .context("The path is not valid UTF-8")?
+        .to_string())
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use regex::Regex;
+
+use crate::database::{
+    commit_link::REFERENCE,
+    todo::{CodeTodo, IssueReference, TodoMarker},
+};
+
+/// Scans the text files at the head of the checked-out branch for `TODO`,
+/// `FIXME` and `XXX` markers. A `#N` reference refers to an issue of the
+/// scanned repository.
+pub(super) fn scan(repo: &Repository, owner: &str, name: &str) -> Result<Vec<CodeTodo>> {
+    let tree = repo.head()?.peel_to_tree()?;
+    let mut blobs = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(file) = entry.name() {
+                blobs.push((format!("{dir}{file}"), entry.id()));
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let scanner = Scanner::new();
+    let mut todos = Vec::new();
+    for (path, oid) in blobs {
+        let blob = repo.find_blob(oid)?;
+        if blob.is_binary() {
+            continue;
+        }
+        let Ok(contents) = std::str::from_utf8(blob.content()) else {
+            continue;
+        };
+        todos.extend(scanner.scan(&path, contents, owner, name));
+    }
+    Ok(todos)
+}
+
+struct Scanner {
+    marker: Regex,
+}
+
+impl Scanner {
+    fn new() -> Self {
+        Self {
+            marker: Regex::new(r"\b(TODO|FIXME|XXX)\b").expect("valid regex"),
+        }
+    }
+
+    fn scan(&self, path: &str, contents: &str, owner: &str, name: &str) -> Vec<CodeTodo> {
+        contents
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let captures = self.marker.captures(line)?;
+                let marker = captures.get(1)?;
+                let issue = REFERENCE
+                    .captures(&line[marker.end()..])
+                    .and_then(|reference| {
+                        Some(IssueReference {
+                            owner: reference
+                                .name("owner")
+                                .map_or(owner, |owner| owner.as_str())
+                                .to_string(),
+                            repo: reference
+                                .name("repo")
+                                .map_or(name, |repo| repo.as_str())
+                                .to_string(),
+                            number: reference.name("number")?.as_str().parse().ok()?,
+                        })
+                    });
+                Some(CodeTodo {
+                    path: path.to_string(),
+                    line: index + 1,
+                    marker: match marker.as_str() {
+                        "FIXME" => TodoMarker::Fixme,
+                        "XXX" => TodoMarker::Xxx,
+                        _ => TodoMarker::Todo,
+                    },
+                    text: line.trim().to_string(),
+                    issue,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scanner;
+    use crate::database::todo::{IssueReference, TodoMarker};
+
+    #[test]
+    fn scan_markers_and_references() {
+        let contents = "\
+fn main() {
+    // TODO: #181
+    // FIXME(aicers/review#42): handle errors
+    // XXX no reference
+    // TODOS are not markers
+}
+";
+        let todos = Scanner::new().scan("src/main.rs", contents, "aicers", "frontary");
+        assert_eq!(todos.len(), 3);
+        assert_eq!(todos[0].line, 2);
+        assert_eq!(todos[0].marker, TodoMarker::Todo);
+        assert_eq!(todos[0].text, "// TODO: #181");
+        assert_eq!(
+            todos[0].issue,
+            Some(IssueReference {
+                owner: "aicers".to_string(),
+                repo: "frontary".to_string(),
+                number: 181,
+            })
+        );
+        assert_eq!(todos[1].marker, TodoMarker::Fixme);
+        assert_eq!(
+            todos[1].issue,
+            Some(IssueReference {
+                owner: "aicers".to_string(),
+                repo: "review".to_string(),
+                number: 42,
+            })
+        );
+        assert_eq!(todos[2].marker, TodoMarker::Xxx);
+        assert_eq!(todos[2].issue, None);
+    }
+}
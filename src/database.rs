@@ -1,65 +1,182 @@
-use std::path::Path;
+use std::{
+    fs::{self, File},
+    path::Path,
+    sync::Arc,
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use fjall::{Keyspace, PartitionHandle};
-use regex::Regex;
-use serde::Serialize;
-
+use serde::{de::DeserializeOwned, Serialize};
+
+mod backup;
+pub mod code_scanning_alert;
+pub mod code_size;
+pub mod codeowners;
+pub mod commit;
+pub mod commit_link;
+pub mod dependency;
 pub mod discussion;
+pub mod history;
+pub mod index;
 pub mod issue;
+mod migration;
+pub mod ownership;
 pub mod pull_request;
+pub mod reaction;
+pub mod repository;
+pub mod snapshot;
+pub mod sync_status;
+pub mod team;
+pub mod todo;
+pub mod vulnerability_alert;
 
 pub(crate) use discussion::DiscussionDbSchema;
 pub(crate) use issue::GitHubIssue;
 
+/// The file in the database directory that a process holds an exclusive lock
+/// on while it has the database open, as fjall does not guard the directory
+/// against other processes.
+const LOCK_FILE_NAME: &str = "crate.lock";
 const GLOBAL_PARTITION_NAME: &str = "global";
+const CODE_SCANNING_ALERT_PARTITION_NAME: &str = "code_scanning_alerts";
+const CODE_SIZE_PARTITION_NAME: &str = "code_sizes";
+const CODEOWNERS_PARTITION_NAME: &str = "codeowners";
+const COMMIT_PARTITION_NAME: &str = "commits";
+const COMMIT_LINK_PARTITION_NAME: &str = "commit_links";
+const DEPENDENCY_PARTITION_NAME: &str = "dependencies";
 const ISSUE_PARTITION_NAME: &str = "issues";
+const ISSUE_INDEX_PARTITION_NAME: &str = "issue_index";
+const ISSUE_HISTORY_PARTITION_NAME: &str = "issue_history";
+const OWNERSHIP_PARTITION_NAME: &str = "ownership";
 const PULL_REQUEST_PARTITION_NAME: &str = "pull_requests";
+const PULL_REQUEST_INDEX_PARTITION_NAME: &str = "pull_request_index";
+const PULL_REQUEST_HISTORY_PARTITION_NAME: &str = "pull_request_history";
 const DISCUSSION_PARTITION_NAME: &str = "discussions";
+const DISCUSSION_INDEX_PARTITION_NAME: &str = "discussion_index";
+const REPOSITORY_PARTITION_NAME: &str = "repositories";
+const SNAPSHOT_PARTITION_NAME: &str = "snapshots";
+const SYNC_STATUS_PARTITION_NAME: &str = "sync_status";
+const TEAM_PARTITION_NAME: &str = "teams";
+const TODO_PARTITION_NAME: &str = "todos";
+const VULNERABILITY_ALERT_PARTITION_NAME: &str = "vulnerability_alerts";
 
 #[derive(Clone)]
 pub(crate) struct Database {
+    _lock: Arc<File>,
     keyspace: Keyspace,
+    code_scanning_alert_partition: PartitionHandle,
+    code_size_partition: PartitionHandle,
+    codeowners_partition: PartitionHandle,
+    commit_partition: PartitionHandle,
+    commit_link_partition: PartitionHandle,
+    dependency_partition: PartitionHandle,
     issue_partition: PartitionHandle,
+    issue_index_partition: PartitionHandle,
+    issue_history_partition: PartitionHandle,
+    ownership_partition: PartitionHandle,
     pull_request_partition: PartitionHandle,
+    pull_request_index_partition: PartitionHandle,
+    pull_request_history_partition: PartitionHandle,
     discussion_partition: PartitionHandle,
+    discussion_index_partition: PartitionHandle,
+    repository_partition: PartitionHandle,
+    snapshot_partition: PartitionHandle,
+    sync_status_partition: PartitionHandle,
+    team_partition: PartitionHandle,
+    todo_partition: PartitionHandle,
+    vulnerability_alert_partition: PartitionHandle,
 }
 
 impl Database {
+    /// Takes the exclusive lock on the database, which is released when the
+    /// returned file is closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another process, such as a running server, has the
+    /// database open.
+    fn lock(path: &Path) -> Result<File> {
+        fs::create_dir_all(path)?;
+        let file = File::create(path.join(LOCK_FILE_NAME))?;
+        if file.try_lock().is_err() {
+            bail!(
+                "database {} is in use by another process; stop it first",
+                path.display()
+            );
+        }
+        Ok(file)
+    }
+
     fn connect_keyspace(path: &Path) -> Result<Keyspace> {
         Ok(fjall::Config::new(path).open()?)
     }
 
     fn connect_partitions(
         keyspace: &Keyspace,
-    ) -> Result<(PartitionHandle, PartitionHandle, PartitionHandle)> {
+    ) -> Result<(
+        PartitionHandle,
+        PartitionHandle,
+        PartitionHandle,
+        PartitionHandle,
+    )> {
         let options = fjall::PartitionCreateOptions::default();
         let issue_partition = keyspace.open_partition(ISSUE_PARTITION_NAME, options.clone())?;
         let pull_request_partition =
             keyspace.open_partition(PULL_REQUEST_PARTITION_NAME, options.clone())?;
         let discussion_partition =
             keyspace.open_partition(DISCUSSION_PARTITION_NAME, options.clone())?;
+        let repository_partition =
+            keyspace.open_partition(REPOSITORY_PARTITION_NAME, options.clone())?;
         Ok((
             issue_partition,
             pull_request_partition,
             discussion_partition,
+            repository_partition,
         ))
     }
 
     pub(crate) fn connect(db_path: &Path) -> Result<Database> {
+        let lock = Database::lock(db_path)?;
         let keyspace = Database::connect_keyspace(db_path)?;
-        let (issue_partition, pull_request_partition, discussion_partition) =
+        let (issue_partition, pull_request_partition, discussion_partition, repository_partition) =
             Database::connect_partitions(&keyspace)?;
-        Ok(Database {
-            keyspace,
+        let options = fjall::PartitionCreateOptions::default();
+        let open_partition = |name: &str| keyspace.open_partition(name, options.clone());
+        let database = Database {
+            code_scanning_alert_partition: open_partition(CODE_SCANNING_ALERT_PARTITION_NAME)?,
+            code_size_partition: open_partition(CODE_SIZE_PARTITION_NAME)?,
+            codeowners_partition: open_partition(CODEOWNERS_PARTITION_NAME)?,
+            commit_partition: open_partition(COMMIT_PARTITION_NAME)?,
+            commit_link_partition: open_partition(COMMIT_LINK_PARTITION_NAME)?,
+            dependency_partition: open_partition(DEPENDENCY_PARTITION_NAME)?,
             issue_partition,
+            issue_index_partition: open_partition(ISSUE_INDEX_PARTITION_NAME)?,
+            issue_history_partition: open_partition(ISSUE_HISTORY_PARTITION_NAME)?,
+            ownership_partition: open_partition(OWNERSHIP_PARTITION_NAME)?,
             pull_request_partition,
+            pull_request_index_partition: open_partition(PULL_REQUEST_INDEX_PARTITION_NAME)?,
+            pull_request_history_partition: open_partition(PULL_REQUEST_HISTORY_PARTITION_NAME)?,
             discussion_partition,
-        })
+            discussion_index_partition: open_partition(DISCUSSION_INDEX_PARTITION_NAME)?,
+            repository_partition,
+            snapshot_partition: open_partition(SNAPSHOT_PARTITION_NAME)?,
+            sync_status_partition: open_partition(SYNC_STATUS_PARTITION_NAME)?,
+            team_partition: open_partition(TEAM_PARTITION_NAME)?,
+            todo_partition: open_partition(TODO_PARTITION_NAME)?,
+            vulnerability_alert_partition: open_partition(VULNERABILITY_ALERT_PARTITION_NAME)?,
+            _lock: Arc::new(lock),
+            keyspace,
+        };
+        database.migrate()?;
+        Ok(database)
     }
 
-    fn insert<T: Serialize>(key: &str, val: T, partition: &PartitionHandle) -> Result<()> {
-        partition.insert(key, bincode::serialize(&val)?)?;
+    fn insert<K: AsRef<[u8]>, T: Serialize>(
+        key: K,
+        val: T,
+        partition: &PartitionHandle,
+    ) -> Result<()> {
+        partition.insert(key.as_ref(), encode(&val)?)?;
         Ok(())
     }
 
@@ -85,6 +202,30 @@ impl Database {
     }
 }
 
+/// The length of the header of a stored value, which holds the storage
+/// version the value was stored with in big-endian.
+const VERSION_HEADER_LEN: usize = 2;
+
+/// Serializes a value to be stored, prefixed with the current storage
+/// version. Values in the global partition are stored without a header.
+pub(crate) fn encode<T: Serialize>(val: &T) -> Result<Vec<u8>> {
+    let mut value = migration::STORAGE_VERSION.to_be_bytes().to_vec();
+    bincode::serialize_into(&mut value, val)?;
+    Ok(value)
+}
+
+/// Deserializes a stored value, checking its version header.
+pub(crate) fn decode<T: DeserializeOwned>(value: &[u8]) -> Result<T> {
+    let Some((header, body)) = value.split_first_chunk::<VERSION_HEADER_LEN>() else {
+        bail!("stored value has no version header");
+    };
+    let version = u16::from_be_bytes(*header);
+    if version > migration::STORAGE_VERSION {
+        bail!("stored value is of storage version {version}, which is newer than this build");
+    }
+    Ok(bincode::deserialize(body)?)
+}
+
 pub(crate) trait TryFromKeyValue {
     fn try_from_key_value(key: &[u8], value: &[u8]) -> Result<Self>
     where
@@ -130,33 +271,44 @@ impl<T: TryFromKeyValue> DoubleEndedIterator for Iter<T> {
     }
 }
 
+/// Returns the key of an issue, pull request, discussion or vulnerability
+/// alert: the owner and the name of the repository, each followed by `0x00`,
+/// and the number in big-endian, so that the records of a repository are
+/// ordered by number.
+pub(crate) fn record_key(owner: &str, name: &str, number: i32) -> Vec<u8> {
+    let mut key = record_prefix(owner, name);
+    key.extend_from_slice(&number.to_be_bytes());
+    key
+}
+
+/// Returns the prefix of the keys made by `record_key` for the records of a
+/// repository.
+pub(crate) fn record_prefix(owner: &str, name: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(owner.len() + name.len() + 6);
+    prefix.extend_from_slice(owner.as_bytes());
+    prefix.push(0);
+    prefix.extend_from_slice(name.as_bytes());
+    prefix.push(0);
+    prefix
+}
+
+/// Returns the owner, the repository name and the number in a key made by
+/// `record_key`.
 pub(crate) fn parse_key(key: &[u8]) -> Result<(String, String, i32)> {
-    let re = Regex::new(r"(?P<owner>\S+)/(?P<name>\S+)#(?P<number>[0-9]+)").expect("valid regex");
-    if let Some(caps) = re.captures(
-        String::from_utf8(key.to_vec())
-            .context("invalid key")?
-            .as_str(),
-    ) {
-        let owner = caps
-            .name("owner")
-            .ok_or_else(|| anyhow!("invalid key"))?
-            .as_str()
-            .to_string();
-        let name = caps
-            .name("name")
-            .ok_or_else(|| anyhow!("invalid key"))?
-            .as_str()
-            .to_string();
-        let number = caps
-            .name("number")
-            .ok_or_else(|| anyhow!("invalid key"))?
-            .as_str()
-            .parse::<i32>()
-            .context("invalid key")?;
-        Ok((owner, name, number))
-    } else {
-        Err(anyhow!("invalid key"))
-    }
+    let (rest, number) = key
+        .split_last_chunk::<4>()
+        .ok_or_else(|| anyhow!("invalid key"))?;
+    let mut parts = rest.split(|&byte| byte == 0);
+    let (Some(owner), Some(name), Some([]), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        bail!("invalid key");
+    };
+    Ok((
+        String::from_utf8(owner.to_vec()).context("invalid key")?,
+        String::from_utf8(name.to_vec()).context("invalid key")?,
+        i32::from_be_bytes(*number),
+    ))
 }
 
 #[cfg(test)]
@@ -203,11 +355,18 @@ mod tests {
 
     #[test]
     fn parse_key() {
-        let key = "rust-lang/rust#12345";
-        let (owner, name, number) = super::parse_key(key.as_bytes()).unwrap();
+        let key = record_key("rust-lang", "rust", 12345);
+        let (owner, name, number) = super::parse_key(&key).unwrap();
         assert_eq!(owner, "rust-lang");
         assert_eq!(name, "rust");
         assert_eq!(number, 12345);
+        assert!(super::parse_key(b"rust-lang/rust#12345").is_err());
+    }
+
+    #[test]
+    fn record_keys_follow_numbers() {
+        assert!(record_key("aicers", "frontary", 9) < record_key("aicers", "frontary", 10));
+        assert!(record_key("aicers", "frontary", 500) < record_key("aicers", "frontary-ui", 1));
     }
 
     #[test]
@@ -254,6 +413,15 @@ mod tests {
         assert!(iter.next().is_none());
     }
 
+    #[test]
+    fn refuse_database_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        assert!(Database::connect(dir.path()).is_err());
+        drop(db);
+        assert!(Database::connect(dir.path()).is_ok());
+    }
+
     #[test]
     fn iter_reverse_empty() {
         let mock_iter = create_mock_fjall_iter(vec![]);
@@ -0,0 +1,207 @@
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use fjall::PartitionHandle;
+use serde::{Deserialize, Serialize};
+
+use super::{migration::STORAGE_VERSION, Database, GLOBAL_PARTITION_NAME};
+
+/// The version of the export format. Increase it whenever the layout of the
+/// lines changes.
+const EXPORT_FORMAT_VERSION: u16 = 1;
+
+/// The first line of an export.
+#[derive(Debug, Deserialize, Serialize)]
+struct ExportHeader {
+    format_version: u16,
+    /// The storage version of the exported values, so that an import can
+    /// migrate them.
+    storage_version: u16,
+}
+
+/// A line of an export after the header, holding a stored key-value pair as
+/// is, encoded in base64.
+#[derive(Debug, Deserialize, Serialize)]
+struct ExportEntry {
+    partition: String,
+    key: String,
+    value: String,
+}
+
+impl Database {
+    /// Writes the header and every stored key-value pair, one JSON object per
+    /// line, and returns the number of pairs written.
+    ///
+    /// Every partition is read from a snapshot taken at the same instant, so
+    /// the export is consistent even while the server writes to the database.
+    /// The indexes are left out, as they are rebuilt on import.
+    pub(crate) fn export<W: Write>(&self, mut writer: W) -> Result<usize> {
+        let instant = self.keyspace.instant();
+        let header = ExportHeader {
+            format_version: EXPORT_FORMAT_VERSION,
+            storage_version: self.storage_version()?,
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writeln!(writer)?;
+
+        let mut count = 0;
+        for (name, partition) in self.exported_partitions()? {
+            for item in partition.snapshot_at(instant).iter() {
+                let (key, value) = item?;
+                let entry = ExportEntry {
+                    partition: name.to_string(),
+                    key: general_purpose::STANDARD.encode(key),
+                    value: general_purpose::STANDARD.encode(value),
+                };
+                serde_json::to_writer(&mut writer, &entry)?;
+                writeln!(writer)?;
+                count += 1;
+            }
+        }
+        writer.flush()?;
+        Ok(count)
+    }
+
+    /// Restores the key-value pairs written by `export`, migrates them to the
+    /// current storage version, rebuilds the indexes, and returns the number
+    /// of pairs restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database already has records, or if the input
+    /// is not an export this build can read.
+    pub(crate) fn import<R: BufRead>(&self, reader: R) -> Result<usize> {
+        let partitions = self.exported_partitions()?;
+        for (name, partition) in &partitions {
+            if *name != GLOBAL_PARTITION_NAME && !partition.is_empty()? {
+                bail!("cannot import into a database with records in partition {name}");
+            }
+        }
+
+        let mut lines = reader.lines();
+        let header = lines.next().context("missing export header")??;
+        let header: ExportHeader =
+            serde_json::from_str(&header).context("invalid export header")?;
+        if header.format_version != EXPORT_FORMAT_VERSION {
+            bail!(
+                "export is of format version {}, but this build supports {EXPORT_FORMAT_VERSION}",
+                header.format_version
+            );
+        }
+        if header.storage_version > STORAGE_VERSION {
+            bail!(
+                "export is of storage version {}, but this build supports up to {STORAGE_VERSION}",
+                header.storage_version
+            );
+        }
+
+        // Every line is checked before anything is written, and the entries
+        // are written in a single batch, so that a failed import leaves the
+        // database empty.
+        let mut count = 0;
+        let mut batch = self.keyspace.batch();
+        for (number, line) in (2..).zip(lines) {
+            let entry: ExportEntry = serde_json::from_str(&line?)
+                .with_context(|| format!("invalid entry on line {number}"))?;
+            let (_, partition) = partitions
+                .iter()
+                .find(|(name, _)| *name == entry.partition)
+                .ok_or_else(|| anyhow!("unknown partition on line {number}"))?;
+            let key = general_purpose::STANDARD
+                .decode(&entry.key)
+                .with_context(|| format!("invalid key on line {number}"))?;
+            let value = general_purpose::STANDARD
+                .decode(&entry.value)
+                .with_context(|| format!("invalid value on line {number}"))?;
+            batch.insert(partition, key, value);
+            count += 1;
+        }
+        batch.commit()?;
+
+        // The global partition holds the storage version of the export.
+        self.migrate()?;
+        self.rebuild_indexes()?;
+        Ok(count)
+    }
+
+    /// Returns the partitions written by `export`, with their names.
+    fn exported_partitions(&self) -> Result<Vec<(&'static str, PartitionHandle)>> {
+        let global = self.keyspace.open_partition(
+            GLOBAL_PARTITION_NAME,
+            fjall::PartitionCreateOptions::default(),
+        )?;
+        let mut partitions = vec![(GLOBAL_PARTITION_NAME, global)];
+        partitions.extend(
+            self.data_partitions()
+                .into_iter()
+                .map(|(name, partition)| (name, partition.clone())),
+        );
+        Ok(partitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::{index::IndexFilter, Database, GitHubIssue};
+
+    #[test]
+    fn export_and_import() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = Database::connect(source_dir.path()).unwrap();
+        let issue = || GitHubIssue {
+            number: 3,
+            author: "alice".to_string(),
+            ..Default::default()
+        };
+        source
+            .insert_issues(vec![issue()], "aicers", "frontary")
+            .unwrap();
+        source.insert_db("last_sync", "2025-01-06").unwrap();
+        let mut exported = Vec::new();
+        assert_eq!(source.export(&mut exported).unwrap(), 3);
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = Database::connect(target_dir.path()).unwrap();
+        assert_eq!(target.import(exported.as_slice()).unwrap(), 3);
+        assert_eq!(
+            target.issue("aicers", "frontary", 3).unwrap(),
+            Some(issue())
+        );
+        assert_eq!(target.select_db("last_sync").unwrap(), "2025-01-06");
+        let authors: Vec<_> = target
+            .issues_matching(&IndexFilter {
+                authors: Some(vec!["alice"]),
+                ..Default::default()
+            })
+            .unwrap()
+            .map(|issue| issue.unwrap().number)
+            .collect();
+        assert_eq!(authors, [3]);
+
+        // A database with records is not overwritten.
+        assert!(target.import(exported.as_slice()).is_err());
+    }
+
+    #[test]
+    fn import_nothing_from_invalid_export() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = Database::connect(source_dir.path()).unwrap();
+        source
+            .insert_issues(vec![GitHubIssue::default()], "aicers", "frontary")
+            .unwrap();
+        let mut exported = Vec::new();
+        source.export(&mut exported).unwrap();
+        let mut truncated = exported.clone();
+        truncated.extend_from_slice(b"{\"partition\":\"issues\"\n");
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = Database::connect(target_dir.path()).unwrap();
+        assert!(target.import(truncated.as_slice()).is_err());
+        assert_eq!(target.issue("aicers", "frontary", 0).unwrap(), None);
+
+        // The import can be retried, as nothing was written.
+        assert!(target.import(exported.as_slice()).is_ok());
+        assert!(target.issue("aicers", "frontary", 0).unwrap().is_some());
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{Error, Result};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::{Database, Iter};
+use crate::{api::code_scanning_alert::CodeScanningAlert, outbound::CodeScanningAlertNode};
+
+impl Database {
+    pub(crate) fn insert_code_scanning_alerts(
+        &self,
+        resp: Vec<GitHubCodeScanningAlert>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        for item in resp {
+            let key = super::record_key(owner, name, item.number);
+            Database::insert(key, item, &self.code_scanning_alert_partition)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn code_scanning_alerts(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Iter<CodeScanningAlert> {
+        let start = start.unwrap_or(b"\x00");
+        if let Some(end) = end {
+            Iter::new(self.code_scanning_alert_partition.range(start..end))
+        } else {
+            Iter::new(self.code_scanning_alert_partition.range(start..))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct GitHubCodeScanningAlert {
+    pub(crate) number: i32,
+    /// The ID of the rule that raised the alert.
+    pub(crate) rule: String,
+    /// The name of the tool that ran the rule, such as `CodeQL`.
+    pub(crate) tool: String,
+    /// The security severity of the rule, such as `high`, or `None` if the
+    /// rule is not a security rule.
+    pub(crate) security_severity: Option<String>,
+    pub(crate) state: CodeScanningAlertState,
+    pub(crate) created_at: Timestamp,
+    pub(crate) fixed_at: Option<Timestamp>,
+    pub(crate) dismissed_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CodeScanningAlertState {
+    Open,
+    Dismissed,
+    Fixed,
+}
+
+impl TryFrom<CodeScanningAlertNode> for GitHubCodeScanningAlert {
+    type Error = Error;
+
+    fn try_from(alert: CodeScanningAlertNode) -> Result<Self> {
+        Ok(Self {
+            number: alert.number.try_into()?,
+            rule: alert.rule.id.unwrap_or_default(),
+            tool: alert.tool.name.unwrap_or_default(),
+            security_severity: alert.rule.security_severity_level,
+            state: alert.state,
+            created_at: alert.created_at,
+            fixed_at: alert.fixed_at,
+            dismissed_at: alert.dismissed_at,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    /// Stores the line counts of the repository.
+    ///
+    /// Snapshots are keyed by the UTC date they were taken on, so only the
+    /// last snapshot of each day is kept.
+    pub(crate) fn insert_code_size_snapshot(
+        &self,
+        snapshot: CodeSizeSnapshot,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        let keystr = format!("{owner}/{name}/{}", snapshot.taken_at.strftime("%Y-%m-%d"));
+        Database::insert(&keystr, snapshot, &self.code_size_partition)
+    }
+
+    /// Returns the line counts of the repository, oldest first.
+    pub(crate) fn code_size_snapshots(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Vec<CodeSizeSnapshot>> {
+        self.code_size_partition
+            .prefix(format!("{owner}/{name}/"))
+            .map(|item| {
+                let (_, value) = item?;
+                super::decode(&value)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CodeSizeSnapshot {
+    pub(crate) taken_at: Timestamp,
+    pub(crate) languages: Vec<LanguageLineCount>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct LanguageLineCount {
+    pub(crate) language: String,
+    pub(crate) files: usize,
+    pub(crate) code: usize,
+    pub(crate) comment: usize,
+    pub(crate) blank: usize,
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    /// Stores the rules of the `CODEOWNERS` file of the repository, replacing
+    /// the previously stored rules.
+    pub(crate) fn insert_codeowners(
+        &self,
+        rules: Vec<CodeownerRule>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        Database::insert(format!("{owner}/{name}"), rules, &self.codeowners_partition)
+    }
+
+    /// Returns the rules of the `CODEOWNERS` file of the repository, or `None`
+    /// if the clone has not been analyzed yet.
+    pub(crate) fn codeowners(&self, owner: &str, name: &str) -> Result<Option<Vec<CodeownerRule>>> {
+        self.codeowners_partition
+            .get(format!("{owner}/{name}"))?
+            .map(|value| super::decode(&value))
+            .transpose()
+    }
+}
+
+/// A line of a `CODEOWNERS` file.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CodeownerRule {
+    /// The gitignore-style pattern of the paths the rule applies to.
+    pub(crate) pattern: String,
+    /// The `@user`, `@org/team` or email owners. Empty if the rule removes
+    /// the owners of the matching paths.
+    pub(crate) owners: Vec<String>,
+}
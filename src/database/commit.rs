@@ -0,0 +1,104 @@
+use anyhow::Result;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::{commit_link::LinkedCommit, Database, Iter, TryFromKeyValue};
+use crate::api::commit::Commit;
+
+impl Database {
+    pub(crate) fn insert_commits(
+        &self,
+        resp: Vec<GitCommit>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        for item in resp {
+            let keystr = format!("{owner}/{name}/{}", item.oid);
+            Database::insert(&keystr, item, &self.commit_partition)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn commits(&self, start: Option<&[u8]>, end: Option<&[u8]>) -> Iter<Commit> {
+        let start = start.unwrap_or(b"\x00");
+        if let Some(end) = end {
+            Iter::new(self.commit_partition.range(start..end))
+        } else {
+            Iter::new(self.commit_partition.range(start..))
+        }
+    }
+
+    /// Returns the commits of the repository, in no particular order.
+    pub(crate) fn repository_commits(&self, owner: &str, name: &str) -> Iter<Commit> {
+        Iter::new(self.commit_partition.prefix(format!("{owner}/{name}/")))
+    }
+
+    /// Removes the stored commits of the repository, along with their links
+    /// to issues and pull requests.
+    pub(crate) fn remove_repository_commits(&self, owner: &str, name: &str) -> Result<()> {
+        let keys = self
+            .commit_partition
+            .prefix(format!("{owner}/{name}/"))
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<fjall::Result<Vec<_>>>()?;
+        let mut link_keys = Vec::new();
+        for item in self.commit_link_partition.iter() {
+            let (key, value) = item?;
+            let link: LinkedCommit = super::decode(&value)?;
+            if link.owner == owner && link.repo == name {
+                link_keys.push(key);
+            }
+        }
+        let mut batch = self.keyspace.batch();
+        for key in keys {
+            batch.remove(&self.commit_partition, key);
+        }
+        for key in link_keys {
+            batch.remove(&self.commit_link_partition, key);
+        }
+        batch.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn commit(&self, owner: &str, name: &str, oid: &str) -> Result<Option<Commit>> {
+        let key = format!("{owner}/{name}/{oid}");
+        self.commit_partition
+            .get(&key)?
+            .map(|value| Commit::try_from_key_value(key.as_bytes(), &value))
+            .transpose()
+    }
+
+    /// Returns the last commit whose history has been stored, if any.
+    pub(crate) fn commit_head(&self, owner: &str, name: &str) -> Option<String> {
+        self.select_db(&format!("commit_head/{owner}/{name}")).ok()
+    }
+
+    pub(crate) fn insert_commit_head(&self, owner: &str, name: &str, oid: &str) -> Result<()> {
+        self.insert_db(&format!("commit_head/{owner}/{name}"), oid)
+    }
+}
+
+/// A commit read from a local clone.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitCommit {
+    pub(crate) oid: String,
+    pub(crate) author_name: String,
+    pub(crate) author_email: String,
+    pub(crate) authored_at: Timestamp,
+    pub(crate) committer_name: String,
+    pub(crate) committer_email: String,
+    pub(crate) committed_at: Timestamp,
+    pub(crate) message: String,
+    pub(crate) parent_count: usize,
+    /// The changes against the first parent, or against an empty tree for a
+    /// root commit. Empty for a commit at the boundary of a shallow clone, as
+    /// its parents were not fetched.
+    pub(crate) files: Vec<GitFileChange>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitFileChange {
+    pub(crate) path: String,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+}
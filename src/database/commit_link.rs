@@ -0,0 +1,154 @@
+use std::{collections::BTreeMap, sync::LazyLock};
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+static MERGE_PULL_REQUEST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^Merge pull request #(?P<number>[0-9]+) from ").expect("valid regex")
+});
+static SQUASHED_PULL_REQUEST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(#(?P<number>[0-9]+)\)$").expect("valid regex"));
+static CLOSING_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?):?\s+(?:(?P<owner>[\w.-]+)/(?P<repo>[\w.-]+))?#(?P<number>[0-9]+)\b",
+    )
+    .expect("valid regex")
+});
+/// A reference to an issue or a pull request, as `#N` or `owner/repo#N`.
+pub(crate) static REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\b(?P<owner>[\w.-]+)/(?P<repo>[\w.-]+))?#(?P<number>[0-9]+)\b")
+        .expect("valid regex")
+});
+
+impl Database {
+    /// Stores the links of a commit to the issues and pull requests its
+    /// message refers to.
+    pub(crate) fn insert_commit_links(
+        &self,
+        links: Vec<CommitLink>,
+        owner: &str,
+        name: &str,
+        oid: &str,
+    ) -> Result<()> {
+        for link in links {
+            let keystr = format!(
+                "{}/{}#{}/{owner}/{name}/{oid}",
+                link.owner, link.repo, link.number
+            );
+            let value = LinkedCommit {
+                owner: owner.to_string(),
+                repo: name.to_string(),
+                oid: oid.to_string(),
+                kind: link.kind,
+            };
+            Database::insert(&keystr, value, &self.commit_link_partition)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the commits linked to the issue or pull request with the given
+    /// number.
+    pub(crate) fn linked_commits(
+        &self,
+        owner: &str,
+        name: &str,
+        number: i32,
+    ) -> Result<Vec<LinkedCommit>> {
+        self.commit_link_partition
+            .prefix(format!("{owner}/{name}#{number}/"))
+            .map(|item| {
+                let (_, value) = item?;
+                super::decode(&value)
+            })
+            .collect()
+    }
+
+    /// Returns whether the links of the commits of the repository stored
+    /// before commits were linked have been added.
+    pub(crate) fn commit_links_indexed(&self, owner: &str, name: &str) -> bool {
+        self.select_db(&format!("commit_links_indexed/{owner}/{name}"))
+            .is_ok()
+    }
+
+    pub(crate) fn insert_commit_links_indexed(&self, owner: &str, name: &str) -> Result<()> {
+        self.insert_db(&format!("commit_links_indexed/{owner}/{name}"), "true")
+    }
+}
+
+/// A reference from a commit message to an issue or a pull request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommitLink {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+    pub(crate) kind: CommitLinkKind,
+}
+
+/// How a commit refers to an issue or a pull request.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum CommitLinkKind {
+    /// The message mentions `#N` or `owner/repo#N`.
+    Reference,
+    /// The message closes the issue with a keyword such as `Fixes #N`.
+    Closes,
+    /// The commit merged the pull request, either as a merge commit or as a
+    /// squashed commit whose subject ends with `(#N)`.
+    Merge,
+}
+
+/// A commit linked to an issue or a pull request.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct LinkedCommit {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) oid: String,
+    pub(crate) kind: CommitLinkKind,
+}
+
+/// Returns the issues and pull requests a commit message refers to. `#N`
+/// refers to the repository of the commit. If a message refers to the same
+/// number more than once, the strongest link is kept.
+pub(crate) fn parse_commit_links(message: &str, owner: &str, name: &str) -> Vec<CommitLink> {
+    let mut links: BTreeMap<(String, String, i32), CommitLinkKind> = BTreeMap::new();
+    let mut add = |captures: regex::Captures, kind: CommitLinkKind| {
+        let Some(number) = captures
+            .name("number")
+            .and_then(|number| number.as_str().parse::<i32>().ok())
+        else {
+            return;
+        };
+        let (repo_owner, repo_name) = match (captures.name("owner"), captures.name("repo")) {
+            (Some(repo_owner), Some(repo_name)) => (repo_owner.as_str(), repo_name.as_str()),
+            _ => (owner, name),
+        };
+        let entry = links
+            .entry((repo_owner.to_string(), repo_name.to_string(), number))
+            .or_insert(kind);
+        *entry = (*entry).max(kind);
+    };
+
+    let subject = message.lines().next().unwrap_or_default().trim_end();
+    for regex in [&*MERGE_PULL_REQUEST, &*SQUASHED_PULL_REQUEST] {
+        if let Some(captures) = regex.captures(subject) {
+            add(captures, CommitLinkKind::Merge);
+        }
+    }
+    for captures in CLOSING_REFERENCE.captures_iter(message) {
+        add(captures, CommitLinkKind::Closes);
+    }
+    for captures in REFERENCE.captures_iter(message) {
+        add(captures, CommitLinkKind::Reference);
+    }
+    links
+        .into_iter()
+        .map(|((owner, repo, number), kind)| CommitLink {
+            owner,
+            repo,
+            number,
+            kind,
+        })
+        .collect()
+}
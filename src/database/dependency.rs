@@ -0,0 +1,56 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    /// Stores the Cargo dependencies of the repository, replacing the
+    /// previously stored ones.
+    pub(crate) fn insert_dependencies(
+        &self,
+        dependencies: Vec<CargoDependency>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        Database::insert(
+            format!("{owner}/{name}"),
+            dependencies,
+            &self.dependency_partition,
+        )
+    }
+
+    /// Returns the Cargo dependencies of the repository, or an empty list if
+    /// the clone has not been analyzed yet.
+    pub(crate) fn dependencies(&self, owner: &str, name: &str) -> Result<Vec<CargoDependency>> {
+        Ok(self
+            .dependency_partition
+            .get(format!("{owner}/{name}"))?
+            .map(|value| super::decode(&value))
+            .transpose()?
+            .unwrap_or_default())
+    }
+}
+
+/// A dependency declared in a `Cargo.toml` of a repository.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CargoDependency {
+    /// The path of the `Cargo.toml` declaring the dependency.
+    pub(crate) manifest: String,
+    /// The name of the crate, not the name it is renamed to.
+    pub(crate) name: String,
+    pub(crate) kind: DependencyKind,
+    /// The version requirement, if any.
+    pub(crate) requirement: Option<String>,
+    /// The version locked in the `Cargo.lock` of the workspace, if any.
+    pub(crate) resolved: Option<String>,
+    /// The features enabled in addition to the default ones.
+    pub(crate) features: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub(crate) enum DependencyKind {
+    #[default]
+    Normal,
+    Dev,
+    Build,
+}
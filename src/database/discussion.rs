@@ -117,8 +117,16 @@ impl Database {
         repo: &str,
     ) -> Result<()> {
         for item in resp {
-            let keystr: String = format!("{owner}/{repo}#{}", item.number);
-            Database::insert(&keystr, item, &self.discussion_partition)?;
+            let key = super::record_key(owner, repo, item.number);
+            let mut batch = self.keyspace.batch();
+            Self::insert_indexed(
+                &mut batch,
+                &key,
+                &item,
+                &self.discussion_partition,
+                &self.discussion_index_partition,
+            )?;
+            batch.commit()?;
         }
         Ok(())
     }
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use fjall::{Batch, PartitionHandle};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::{pull_request::GitHubPullRequestNode, record_key, Database, GitHubIssue};
+
+/// The fields of issues and pull requests whose changes are recorded.
+pub(crate) struct TrackedFields {
+    pub(crate) title: String,
+    pub(crate) state: String,
+    pub(crate) labels: Vec<String>,
+    pub(crate) assignees: Vec<String>,
+    /// The status of the record in each project, keyed by project title.
+    pub(crate) project_statuses: BTreeMap<String, String>,
+}
+
+pub(crate) trait Tracked {
+    fn tracked_fields(&self) -> TrackedFields;
+}
+
+impl Tracked for GitHubIssue {
+    fn tracked_fields(&self) -> TrackedFields {
+        TrackedFields {
+            title: self.title.clone(),
+            state: format!("{:?}", self.state),
+            labels: self.labels.clone(),
+            assignees: self.assignees.clone(),
+            project_statuses: self
+                .project_items
+                .nodes
+                .iter()
+                .filter_map(|item| {
+                    item.todo_status
+                        .clone()
+                        .map(|status| (item.project_title.clone(), status))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Tracked for GitHubPullRequestNode {
+    fn tracked_fields(&self) -> TrackedFields {
+        TrackedFields {
+            title: self.title.clone(),
+            state: format!("{:?}", self.state),
+            labels: self.labels.clone(),
+            assignees: self.assignees.clone(),
+            project_statuses: BTreeMap::new(),
+        }
+    }
+}
+
+/// Adds the changes between the stored and the fetched versions of a record
+/// to `batch`, to be appended to the history partition.
+pub(super) fn insert_changes<T: Tracked>(
+    batch: &mut Batch,
+    old: &T,
+    new: &T,
+    key: &[u8],
+    changed_at: Timestamp,
+    history: &PartitionHandle,
+) -> Result<()> {
+    let changes = diff(&old.tracked_fields(), &new.tracked_fields(), changed_at);
+    for (sequence, change) in (0_u16..).zip(changes) {
+        let mut history_key = key.to_vec();
+        history_key.extend_from_slice(&changed_at.as_nanosecond().to_be_bytes());
+        history_key.extend_from_slice(&sequence.to_be_bytes());
+        batch.insert(history, history_key, super::encode(&change)?);
+    }
+    Ok(())
+}
+
+impl Database {
+    /// Returns the changes of the issue, oldest first.
+    pub(crate) fn issue_history(
+        &self,
+        owner: &str,
+        name: &str,
+        number: i32,
+    ) -> Result<Vec<FieldChange>> {
+        history(&self.issue_history_partition, owner, name, number)
+    }
+
+    /// Returns the changes of the pull request, oldest first.
+    pub(crate) fn pull_request_history(
+        &self,
+        owner: &str,
+        name: &str,
+        number: i32,
+    ) -> Result<Vec<FieldChange>> {
+        history(&self.pull_request_history_partition, owner, name, number)
+    }
+}
+
+fn history(
+    partition: &PartitionHandle,
+    owner: &str,
+    name: &str,
+    number: i32,
+) -> Result<Vec<FieldChange>> {
+    partition
+        .prefix(record_key(owner, name, number))
+        .map(|item| {
+            let (_, value) = item?;
+            super::decode(&value)
+        })
+        .collect()
+}
+
+/// Returns the changes from `old` to `new`. A change of a list is recorded as
+/// an entry for each added or removed item.
+fn diff(old: &TrackedFields, new: &TrackedFields, changed_at: Timestamp) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut push = |field, project: Option<&String>, from: Option<&String>, to: Option<&String>| {
+        changes.push(FieldChange {
+            changed_at,
+            field,
+            project: project.cloned(),
+            from: from.cloned(),
+            to: to.cloned(),
+        });
+    };
+
+    if old.title != new.title {
+        push(
+            ChangedField::Title,
+            None,
+            Some(&old.title),
+            Some(&new.title),
+        );
+    }
+    if old.state != new.state {
+        push(
+            ChangedField::State,
+            None,
+            Some(&old.state),
+            Some(&new.state),
+        );
+    }
+    for (field, old_items, new_items) in [
+        (ChangedField::Labels, &old.labels, &new.labels),
+        (ChangedField::Assignees, &old.assignees, &new.assignees),
+    ] {
+        for removed in old_items.iter().filter(|item| !new_items.contains(item)) {
+            push(field, None, Some(removed), None);
+        }
+        for added in new_items.iter().filter(|item| !old_items.contains(item)) {
+            push(field, None, None, Some(added));
+        }
+    }
+    for (project, status) in &old.project_statuses {
+        let new_status = new.project_statuses.get(project);
+        if new_status != Some(status) {
+            push(
+                ChangedField::ProjectStatus,
+                Some(project),
+                Some(status),
+                new_status,
+            );
+        }
+    }
+    for (project, status) in &new.project_statuses {
+        if !old.project_statuses.contains_key(project) {
+            push(
+                ChangedField::ProjectStatus,
+                Some(project),
+                None,
+                Some(status),
+            );
+        }
+    }
+    changes
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+pub(crate) enum ChangedField {
+    Title,
+    State,
+    Labels,
+    Assignees,
+    ProjectStatus,
+}
+
+/// A change of a field of an issue or a pull request, found when a sync
+/// overwrote the record.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct FieldChange {
+    pub(crate) changed_at: Timestamp,
+    pub(crate) field: ChangedField,
+    /// The title of the project, for a change of `ProjectStatus`.
+    pub(crate) project: Option<String>,
+    /// The previous value, or `None` if an item was added to a list.
+    pub(crate) from: Option<String>,
+    /// The new value, or `None` if an item was removed from a list.
+    pub(crate) to: Option<String>,
+}
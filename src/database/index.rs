@@ -0,0 +1,501 @@
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::Result;
+use fjall::{Batch, PartitionHandle};
+use jiff::Timestamp;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{parse_key, Database, DiscussionDbSchema, GitHubIssue, Iter, TryFromKeyValue};
+use crate::{
+    api::{issue::Issue, pull_request::PullRequest, Discussion},
+    database::pull_request::GitHubPullRequestNode,
+};
+
+/// The fields of issues, pull requests and discussions kept in their index
+/// partitions.
+///
+/// An index key is the field tag, followed by the value and the key of the
+/// record. String values end with `0x00`; timestamps are stored as eight
+/// big-endian bytes so that they sort in time order.
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum IndexField {
+    Author = 1,
+    Assignee = 2,
+    State = 3,
+    Repo = 4,
+    CreatedAt = 5,
+    UpdatedAt = 6,
+}
+
+/// The values of a record kept in an index partition.
+pub(crate) struct IndexValues {
+    pub(crate) author: String,
+    pub(crate) assignees: Vec<String>,
+    pub(crate) state: Option<String>,
+    pub(crate) created_at: Timestamp,
+    pub(crate) updated_at: Timestamp,
+}
+
+pub(crate) trait Indexed {
+    fn index_values(&self) -> IndexValues;
+}
+
+impl Indexed for GitHubIssue {
+    fn index_values(&self) -> IndexValues {
+        IndexValues {
+            author: self.author.clone(),
+            assignees: self.assignees.clone(),
+            state: Some(format!("{:?}", self.state)),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl Indexed for GitHubPullRequestNode {
+    fn index_values(&self) -> IndexValues {
+        IndexValues {
+            author: self.author.clone(),
+            assignees: self.assignees.clone(),
+            state: Some(format!("{:?}", self.state)),
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+impl Indexed for DiscussionDbSchema {
+    fn index_values(&self) -> IndexValues {
+        IndexValues {
+            author: self.author.clone(),
+            assignees: Vec::new(),
+            state: None,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// The conditions looked up in an index partition. Every condition that is
+/// set must hold.
+///
+/// Timestamps are indexed by the second, so a lookup may return records
+/// slightly outside the range; callers still apply their own filters.
+#[derive(Default)]
+pub(crate) struct IndexFilter<'a> {
+    /// The records written by any of the authors.
+    pub(crate) authors: Option<Vec<&'a str>>,
+    pub(crate) assignee: Option<&'a str>,
+    /// The state, as the name of the GitHub enum variant, such as `OPEN`.
+    pub(crate) state: Option<&'a str>,
+    /// The name of the repository.
+    pub(crate) repo: Option<&'a str>,
+    /// Start of the creation datetime range. (inclusive)
+    pub(crate) created_begin: Option<Timestamp>,
+    /// End of the creation datetime range. (exclusive)
+    pub(crate) created_end: Option<Timestamp>,
+    /// Start of the update datetime range. (inclusive)
+    pub(crate) updated_begin: Option<Timestamp>,
+    /// End of the update datetime range. (exclusive)
+    pub(crate) updated_end: Option<Timestamp>,
+}
+
+fn string_prefix(field: IndexField, value: &str) -> Vec<u8> {
+    let mut prefix = vec![field as u8];
+    prefix.extend_from_slice(value.as_bytes());
+    prefix.push(0);
+    prefix
+}
+
+fn time_prefix(field: IndexField, second: i64) -> Vec<u8> {
+    let mut bytes = second.to_be_bytes();
+    bytes[0] ^= 0x80;
+    let mut prefix = vec![field as u8];
+    prefix.extend_from_slice(&bytes);
+    prefix
+}
+
+fn index_keys(key: &[u8], values: &IndexValues) -> Result<Vec<Vec<u8>>> {
+    let (_, repo, _) = parse_key(key)?;
+    let mut prefixes = vec![
+        string_prefix(IndexField::Author, &values.author),
+        string_prefix(IndexField::Repo, &repo),
+        time_prefix(IndexField::CreatedAt, values.created_at.as_second()),
+        time_prefix(IndexField::UpdatedAt, values.updated_at.as_second()),
+    ];
+    prefixes.extend(
+        values
+            .assignees
+            .iter()
+            .map(|assignee| string_prefix(IndexField::Assignee, assignee)),
+    );
+    if let Some(state) = &values.state {
+        prefixes.push(string_prefix(IndexField::State, state));
+    }
+    Ok(prefixes
+        .into_iter()
+        .map(|mut prefix| {
+            prefix.extend_from_slice(key);
+            prefix
+        })
+        .collect())
+}
+
+/// Returns the key of the record an index entry belongs to.
+fn indexed_record_key(entry: &[u8]) -> Option<&[u8]> {
+    let (&field, rest) = entry.split_first()?;
+    if field == IndexField::CreatedAt as u8 || field == IndexField::UpdatedAt as u8 {
+        rest.get(8..)
+    } else {
+        let end = rest.iter().position(|&byte| byte == 0)?;
+        rest.get(end + 1..)
+    }
+}
+
+/// Returns the index entries of the record with the key, looked up by
+/// scanning the whole index partition.
+fn scan_index_keys(index: &PartitionHandle, key: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut entries = Vec::new();
+    for entry in index.keys() {
+        let entry = entry?;
+        if indexed_record_key(&entry) == Some(key) {
+            entries.push(entry.to_vec());
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns the keys of the records whose field has the value.
+fn lookup_value(
+    index: &PartitionHandle,
+    field: IndexField,
+    value: &str,
+) -> Result<BTreeSet<Vec<u8>>> {
+    let prefix = string_prefix(field, value);
+    index
+        .prefix(&prefix)
+        .map(|item| {
+            let (key, _) = item?;
+            Ok(key[prefix.len()..].to_vec())
+        })
+        .collect()
+}
+
+/// Returns the keys of the records whose field is within `[begin, end)`.
+fn lookup_range(
+    index: &PartitionHandle,
+    field: IndexField,
+    begin: Option<Timestamp>,
+    end: Option<Timestamp>,
+) -> Result<BTreeSet<Vec<u8>>> {
+    let start = time_prefix(field, begin.map_or(i64::MIN, Timestamp::as_second));
+    let end = end.map_or_else(
+        || vec![field as u8 + 1],
+        |end| {
+            if end.subsec_nanosecond() > 0 {
+                time_prefix(field, end.as_second().saturating_add(1))
+            } else {
+                time_prefix(field, end.as_second())
+            }
+        },
+    );
+    let offset = start.len();
+    index
+        .range(start..end)
+        .map(|item| {
+            let (key, _) = item?;
+            Ok(key[offset..].to_vec())
+        })
+        .collect()
+}
+
+/// Returns the keys of the records satisfying the filter, or `None` if the
+/// filter sets no condition.
+fn lookup(index: &PartitionHandle, filter: &IndexFilter) -> Result<Option<BTreeSet<Vec<u8>>>> {
+    let mut sets = Vec::new();
+    if let Some(authors) = &filter.authors {
+        let mut keys = BTreeSet::new();
+        for author in authors {
+            keys.extend(lookup_value(index, IndexField::Author, author)?);
+        }
+        sets.push(keys);
+    }
+    for (field, value) in [
+        (IndexField::Assignee, filter.assignee),
+        (IndexField::State, filter.state),
+        (IndexField::Repo, filter.repo),
+    ] {
+        if let Some(value) = value {
+            sets.push(lookup_value(index, field, value)?);
+        }
+    }
+    for (field, begin, end) in [
+        (
+            IndexField::CreatedAt,
+            filter.created_begin,
+            filter.created_end,
+        ),
+        (
+            IndexField::UpdatedAt,
+            filter.updated_begin,
+            filter.updated_end,
+        ),
+    ] {
+        if begin.is_some() || end.is_some() {
+            sets.push(lookup_range(index, field, begin, end)?);
+        }
+    }
+
+    sets.sort_by_key(BTreeSet::len);
+    let mut sets = sets.into_iter();
+    let Some(mut keys) = sets.next() else {
+        return Ok(None);
+    };
+    for set in sets {
+        keys.retain(|key| set.contains(key));
+    }
+    Ok(Some(keys))
+}
+
+/// Returns the records with the given keys, in key order.
+fn iter_keys<T: TryFromKeyValue>(partition: &PartitionHandle, keys: BTreeSet<Vec<u8>>) -> Iter<T> {
+    let partition = partition.clone();
+    Iter::new(keys.into_iter().filter_map(move |key| {
+        partition
+            .get(&key)
+            .transpose()
+            .map(|value| value.map(|value| (fjall::Slice::from(key), value)))
+    }))
+}
+
+impl Database {
+    /// Adds a record and the updates of its entries in the index partition
+    /// to `batch`. Returns the record it replaces, if the record could be
+    /// decoded.
+    ///
+    /// The index entries of a replaced record that cannot be decoded are
+    /// looked up by scanning the index partition, so that none is left
+    /// behind.
+    pub(super) fn insert_indexed<T: Serialize + DeserializeOwned + Indexed>(
+        batch: &mut Batch,
+        key: &[u8],
+        val: &T,
+        partition: &PartitionHandle,
+        index: &PartitionHandle,
+    ) -> Result<Option<T>> {
+        let (old, old_keys) = match partition.get(key)? {
+            Some(old) => match super::decode::<T>(&old) {
+                Ok(old) => {
+                    let old_keys = index_keys(key, &old.index_values())?;
+                    (Some(old), old_keys)
+                }
+                Err(_) => (None, scan_index_keys(index, key)?),
+            },
+            None => (None, Vec::new()),
+        };
+        let new_keys = index_keys(key, &val.index_values())?;
+
+        let retained: HashSet<&Vec<u8>> = new_keys.iter().collect();
+        for old_key in &old_keys {
+            if !retained.contains(old_key) {
+                batch.remove(index, old_key.clone());
+            }
+        }
+        for new_key in new_keys {
+            batch.insert(index, new_key, Vec::<u8>::new());
+        }
+        batch.insert(partition, key, super::encode(val)?);
+        Ok(old)
+    }
+
+    /// Adds the index entries of the stored records. Records that cannot be
+    /// decoded or whose keys are not made by `record_key` are skipped.
+    pub(super) fn build_indexes(&self) -> Result<()> {
+        Self::build_index::<GitHubIssue>(&self.issue_partition, &self.issue_index_partition)?;
+        Self::build_index::<GitHubPullRequestNode>(
+            &self.pull_request_partition,
+            &self.pull_request_index_partition,
+        )?;
+        Self::build_index::<DiscussionDbSchema>(
+            &self.discussion_partition,
+            &self.discussion_index_partition,
+        )
+    }
+
+    /// Removes every index entry and adds those of the stored records again.
+    pub(super) fn rebuild_indexes(&self) -> Result<()> {
+        for index in [
+            &self.issue_index_partition,
+            &self.pull_request_index_partition,
+            &self.discussion_index_partition,
+        ] {
+            let keys = index.keys().collect::<fjall::Result<Vec<_>>>()?;
+            let mut batch = self.keyspace.batch();
+            for key in keys {
+                batch.remove(index, key);
+            }
+            batch.commit()?;
+        }
+        self.build_indexes()
+    }
+
+    fn build_index<T: DeserializeOwned + Indexed>(
+        partition: &PartitionHandle,
+        index: &PartitionHandle,
+    ) -> Result<()> {
+        for item in partition.iter() {
+            let (key, value) = item?;
+            let Ok(record) = super::decode::<T>(&value) else {
+                continue;
+            };
+            let Ok(entries) = index_keys(&key, &record.index_values()) else {
+                continue;
+            };
+            for index_key in entries {
+                index.insert(index_key, Vec::<u8>::new())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the issues satisfying the filter, looked up in the index.
+    pub(crate) fn issues_matching(&self, filter: &IndexFilter) -> Result<Iter<Issue>> {
+        Ok(match lookup(&self.issue_index_partition, filter)? {
+            Some(keys) => iter_keys(&self.issue_partition, keys),
+            None => self.issues(None, None),
+        })
+    }
+
+    /// Returns the pull requests satisfying the filter, looked up in the index.
+    pub(crate) fn pull_requests_matching(&self, filter: &IndexFilter) -> Result<Iter<PullRequest>> {
+        Ok(match lookup(&self.pull_request_index_partition, filter)? {
+            Some(keys) => iter_keys(&self.pull_request_partition, keys),
+            None => self.pull_requests(None, None),
+        })
+    }
+
+    /// Returns the discussions satisfying the filter, looked up in the index.
+    pub(crate) fn discussions_matching(&self, filter: &IndexFilter) -> Result<Iter<Discussion>> {
+        Ok(match lookup(&self.discussion_index_partition, filter)? {
+            Some(keys) => iter_keys(&self.discussion_partition, keys),
+            None => self.discussions(None, None),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexFilter;
+    use crate::{
+        database::{record_key, Database, GitHubIssue},
+        outbound::issues::IssueState,
+    };
+
+    fn issue(number: i32, author: &str, state: IssueState, created_at: &str) -> GitHubIssue {
+        GitHubIssue {
+            number,
+            author: author.to_string(),
+            state,
+            created_at: created_at.parse().unwrap(),
+            updated_at: created_at.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn numbers(db: &Database, filter: &IndexFilter) -> Vec<i32> {
+        db.issues_matching(filter)
+            .unwrap()
+            .map(|issue| issue.unwrap().number)
+            .collect()
+    }
+
+    #[test]
+    fn lookup_issues() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        db.insert_issues(
+            vec![
+                issue(1, "alice", IssueState::OPEN, "2025-01-01T00:00:00Z"),
+                issue(2, "bob", IssueState::OPEN, "2025-01-02T00:00:00Z"),
+                issue(3, "alice", IssueState::CLOSED, "2025-01-03T00:00:00Z"),
+            ],
+            "aicers",
+            "frontary",
+        )
+        .unwrap();
+        // Updating a record replaces its index entries.
+        db.insert_issues(
+            vec![issue(
+                1,
+                "alice",
+                IssueState::CLOSED,
+                "2025-01-01T00:00:00Z",
+            )],
+            "aicers",
+            "frontary",
+        )
+        .unwrap();
+
+        let filter = IndexFilter {
+            authors: Some(vec!["alice"]),
+            ..Default::default()
+        };
+        assert_eq!(numbers(&db, &filter), [1, 3]);
+        let filter = IndexFilter {
+            state: Some("OPEN"),
+            ..Default::default()
+        };
+        assert_eq!(numbers(&db, &filter), [2]);
+        let filter = IndexFilter {
+            repo: Some("frontary"),
+            created_begin: Some("2025-01-02T00:00:00Z".parse().unwrap()),
+            created_end: Some("2025-01-03T00:00:00Z".parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(numbers(&db, &filter), [2]);
+        let filter = IndexFilter {
+            repo: Some("review"),
+            ..Default::default()
+        };
+        assert!(numbers(&db, &filter).is_empty());
+        assert_eq!(numbers(&db, &IndexFilter::default()), [1, 2, 3]);
+    }
+
+    #[test]
+    fn replace_undecodable_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        let stored = issue(1, "alice", IssueState::OPEN, "2025-01-01T00:00:00Z");
+        db.insert_issues(vec![stored], "aicers", "frontary")
+            .unwrap();
+        db.issue_partition
+            .insert(record_key("aicers", "frontary", 1), [0xff])
+            .unwrap();
+
+        let replacing = issue(1, "bob", IssueState::CLOSED, "2025-01-02T00:00:00Z");
+        db.insert_issues(vec![replacing], "aicers", "frontary")
+            .unwrap();
+        for filter in [
+            IndexFilter {
+                authors: Some(vec!["alice"]),
+                ..Default::default()
+            },
+            IndexFilter {
+                state: Some("OPEN"),
+                ..Default::default()
+            },
+            IndexFilter {
+                created_end: Some("2025-01-02T00:00:00Z".parse().unwrap()),
+                ..Default::default()
+            },
+        ] {
+            assert!(numbers(&db, &filter).is_empty());
+        }
+        let filter = IndexFilter {
+            authors: Some(vec!["bob"]),
+            ..Default::default()
+        };
+        assert_eq!(numbers(&db, &filter), [1]);
+    }
+}
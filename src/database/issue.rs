@@ -2,7 +2,11 @@ use anyhow::{Context, Error, Result};
 use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use super::{Database, Iter};
+use super::{
+    history,
+    reaction::{self, GitHubReactionGroup},
+    Database, Iter,
+};
 use crate::api::issue::Issue;
 use crate::outbound::issues::{
     IssueState, IssuesRepositoryIssuesNodes, IssuesRepositoryIssuesNodesAssignees,
@@ -12,6 +16,8 @@ use crate::outbound::issues::{
     IssuesRepositoryIssuesNodesClosedByPullRequestsReferencesEdgesNodeAuthor::User as PullRequestRefAuthor,
     IssuesRepositoryIssuesNodesComments, IssuesRepositoryIssuesNodesCommentsNodes,
     IssuesRepositoryIssuesNodesCommentsNodesAuthor::User as IssueCommentsAuthor,
+    IssuesRepositoryIssuesNodesCommentsNodesReactionGroups,
+    IssuesRepositoryIssuesNodesCommentsNodesReactionGroupsReactorsNodes::User as CommentReactorUser,
     IssuesRepositoryIssuesNodesLabels, IssuesRepositoryIssuesNodesParent,
     IssuesRepositoryIssuesNodesProjectItems, IssuesRepositoryIssuesNodesProjectItemsNodes,
     IssuesRepositoryIssuesNodesProjectItemsNodesTodoInitiationOption as TodoInitOption,
@@ -19,6 +25,8 @@ use crate::outbound::issues::{
     IssuesRepositoryIssuesNodesProjectItemsNodesTodoPriority as TodoPriority,
     IssuesRepositoryIssuesNodesProjectItemsNodesTodoSize as TodoSize,
     IssuesRepositoryIssuesNodesProjectItemsNodesTodoStatus as TodoStatus,
+    IssuesRepositoryIssuesNodesReactionGroups,
+    IssuesRepositoryIssuesNodesReactionGroupsReactorsNodes::User as IssueReactorUser,
     IssuesRepositoryIssuesNodesSubIssues, IssuesRepositoryIssuesNodesSubIssuesNodes,
     IssuesRepositoryIssuesNodesSubIssuesNodesAuthor::User as SubIssueAuthor, PullRequestState,
 };
@@ -30,9 +38,28 @@ impl Database {
         owner: &str,
         name: &str,
     ) -> Result<()> {
+        let synced_at = Timestamp::now();
         for item in resp {
-            let keystr: String = format!("{owner}/{name}#{}", item.number);
-            Database::insert(&keystr, item, &self.issue_partition)?;
+            let key = super::record_key(owner, name, item.number);
+            let mut batch = self.keyspace.batch();
+            let old = Self::insert_indexed(
+                &mut batch,
+                &key,
+                &item,
+                &self.issue_partition,
+                &self.issue_index_partition,
+            )?;
+            if let Some(old) = old {
+                history::insert_changes(
+                    &mut batch,
+                    &old,
+                    &item,
+                    &key,
+                    synced_at,
+                    &self.issue_history_partition,
+                )?;
+            }
+            batch.commit()?;
         }
         Ok(())
     }
@@ -45,6 +72,19 @@ impl Database {
             Iter::new(self.issue_partition.range(start..))
         }
     }
+
+    /// Returns the issue with the given number, if it has been fetched.
+    pub(crate) fn issue(
+        &self,
+        owner: &str,
+        name: &str,
+        number: i32,
+    ) -> Result<Option<GitHubIssue>> {
+        self.issue_partition
+            .get(super::record_key(owner, name, number))?
+            .map(|value| super::decode(&value))
+            .transpose()
+    }
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
@@ -63,6 +103,7 @@ pub(crate) struct GitHubIssue {
     pub(crate) parent: Option<GitHubParentIssue>,
     pub(crate) url: String,
     pub(crate) closed_by_pull_requests: Vec<GitHubPullRequestRef>,
+    pub(crate) reactions: Vec<GitHubReactionGroup>,
     pub(crate) created_at: Timestamp,
     pub(crate) updated_at: Timestamp,
     pub(crate) closed_at: Option<Timestamp>,
@@ -83,6 +124,7 @@ pub(crate) struct GitHubIssueComment {
     pub(crate) updated_at: Timestamp,
     pub(crate) repository_name: String,
     pub(crate) url: String,
+    pub(crate) reactions: Vec<GitHubReactionGroup>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
@@ -155,6 +197,12 @@ impl TryFrom<IssuesRepositoryIssuesNodes> for GitHubIssue {
             .closed_by_pull_requests_references
             .and_then(|pr| pr.try_into().ok())
             .unwrap_or_default();
+        let reactions = issue
+            .reaction_groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(GitHubReactionGroup::try_from)
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(Self {
             id: issue.id,
@@ -171,6 +219,7 @@ impl TryFrom<IssuesRepositoryIssuesNodes> for GitHubIssue {
             parent,
             url: issue.url,
             closed_by_pull_requests,
+            reactions,
             created_at: issue.created_at,
             updated_at: issue.updated_at,
             closed_at: issue.closed_at,
@@ -224,15 +273,24 @@ impl TryFrom<IssuesRepositoryIssuesNodesComments> for GitHubIssueCommentConnecti
                 .unwrap_or_default()
                 .into_iter()
                 .flatten()
-                .map(GitHubIssueComment::from)
-                .collect(),
+                .map(GitHubIssueComment::try_from)
+                .collect::<Result<Vec<_>>>()?,
         })
     }
 }
 
-impl From<IssuesRepositoryIssuesNodesCommentsNodes> for GitHubIssueComment {
-    fn from(comment: IssuesRepositoryIssuesNodesCommentsNodes) -> Self {
-        Self {
+impl TryFrom<IssuesRepositoryIssuesNodesCommentsNodes> for GitHubIssueComment {
+    type Error = Error;
+
+    fn try_from(comment: IssuesRepositoryIssuesNodesCommentsNodes) -> Result<Self> {
+        let reactions = comment
+            .reaction_groups
+            .unwrap_or_default()
+            .into_iter()
+            .map(GitHubReactionGroup::try_from)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
             author: match comment.author {
                 Some(IssueCommentsAuthor(u)) => u.login,
                 _ => String::new(),
@@ -243,7 +301,52 @@ impl From<IssuesRepositoryIssuesNodesCommentsNodes> for GitHubIssueComment {
             repository_name: comment.repository.name,
             updated_at: comment.updated_at,
             url: comment.url,
-        }
+            reactions,
+        })
+    }
+}
+
+impl TryFrom<IssuesRepositoryIssuesNodesReactionGroups> for GitHubReactionGroup {
+    type Error = Error;
+
+    fn try_from(group: IssuesRepositoryIssuesNodesReactionGroups) -> Result<Self> {
+        Ok(Self {
+            content: reaction::content_name(&group.content),
+            count: group.reactors.total_count.try_into()?,
+            users: group
+                .reactors
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .filter_map(|reactor| match reactor {
+                    IssueReactorUser(u) => Some(u.login),
+                    _ => None,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<IssuesRepositoryIssuesNodesCommentsNodesReactionGroups> for GitHubReactionGroup {
+    type Error = Error;
+
+    fn try_from(group: IssuesRepositoryIssuesNodesCommentsNodesReactionGroups) -> Result<Self> {
+        Ok(Self {
+            content: reaction::content_name(&group.content),
+            count: group.reactors.total_count.try_into()?,
+            users: group
+                .reactors
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .filter_map(|reactor| match reactor {
+                    CommentReactorUser(u) => Some(u.login),
+                    _ => None,
+                })
+                .collect(),
+        })
     }
 }
 
@@ -0,0 +1,413 @@
+use std::sync::LazyLock;
+
+use anyhow::{anyhow, bail, Context, Result};
+use fjall::PartitionHandle;
+use regex::Regex;
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::info;
+
+use self::legacy::{LegacyIssue, LegacyPullRequest};
+use super::{
+    pull_request::GitHubPullRequestNode, record_key, Database, GitHubIssue,
+    CODEOWNERS_PARTITION_NAME, CODE_SCANNING_ALERT_PARTITION_NAME, CODE_SIZE_PARTITION_NAME,
+    COMMIT_LINK_PARTITION_NAME, COMMIT_PARTITION_NAME, DEPENDENCY_PARTITION_NAME,
+    DISCUSSION_PARTITION_NAME, GLOBAL_PARTITION_NAME, ISSUE_HISTORY_PARTITION_NAME,
+    ISSUE_PARTITION_NAME, OWNERSHIP_PARTITION_NAME, PULL_REQUEST_HISTORY_PARTITION_NAME,
+    PULL_REQUEST_PARTITION_NAME, REPOSITORY_PARTITION_NAME, SNAPSHOT_PARTITION_NAME,
+    SYNC_STATUS_PARTITION_NAME, TEAM_PARTITION_NAME, TODO_PARTITION_NAME, VERSION_HEADER_LEN,
+    VULNERABILITY_ALERT_PARTITION_NAME,
+};
+
+mod legacy;
+
+/// The version of the storage format. It is written in the header of every
+/// stored value, so that a migration can tell the layout a value was stored
+/// with.
+///
+/// Increase it and add a migration to `MIGRATIONS` whenever a stored type or
+/// the layout of the keys changes.
+pub(crate) const STORAGE_VERSION: u16 = 3;
+
+const STORAGE_VERSION_KEY: &str = "storage_version";
+
+/// The keys of the records before storage version 3, `owner/name#number`.
+static LEGACY_RECORD_KEY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<owner>[^/]+)/(?P<name>[^#]+)#(?P<number>[0-9]+)$").expect("valid regex")
+});
+
+type Migration = fn(&Database) -> Result<()>;
+
+/// The migrations, each with the version it upgrades the database to, in
+/// order.
+const MIGRATIONS: [(u16, Migration); 3] = [
+    (1, add_version_headers),
+    (2, build_indexes),
+    (3, use_numeric_record_keys),
+];
+
+impl Database {
+    /// Returns the version of the storage format of the database. A database
+    /// created before the format was versioned is of version 0.
+    pub(crate) fn storage_version(&self) -> Result<u16> {
+        let global_partition = self.keyspace.open_partition(
+            GLOBAL_PARTITION_NAME,
+            fjall::PartitionCreateOptions::default(),
+        )?;
+        let Some(version) = global_partition.get(STORAGE_VERSION_KEY)? else {
+            return Ok(0);
+        };
+        let version: String = bincode::deserialize(&version)?;
+        version.parse().context("invalid storage version")
+    }
+
+    /// Upgrades the database to `STORAGE_VERSION` by running the migrations
+    /// it has not been through yet.
+    pub(super) fn migrate(&self) -> Result<()> {
+        let mut version = self.storage_version()?;
+        if version > STORAGE_VERSION {
+            bail!(
+                "database is of storage version {version}, \
+                 but this build supports up to {STORAGE_VERSION}"
+            );
+        }
+        for (target, migration) in MIGRATIONS {
+            if version >= target {
+                continue;
+            }
+            info!("Migrating the database from storage version {version} to {target}");
+            migration(self).with_context(|| format!("migration to version {target} failed"))?;
+            self.insert_db(STORAGE_VERSION_KEY, target.to_string())?;
+            version = target;
+        }
+        Ok(())
+    }
+
+    /// Returns the partitions whose values are stored with a version header,
+    /// with their names.
+    pub(super) fn data_partitions(&self) -> [(&'static str, &PartitionHandle); 18] {
+        [
+            (
+                CODE_SCANNING_ALERT_PARTITION_NAME,
+                &self.code_scanning_alert_partition,
+            ),
+            (CODE_SIZE_PARTITION_NAME, &self.code_size_partition),
+            (CODEOWNERS_PARTITION_NAME, &self.codeowners_partition),
+            (COMMIT_PARTITION_NAME, &self.commit_partition),
+            (COMMIT_LINK_PARTITION_NAME, &self.commit_link_partition),
+            (DEPENDENCY_PARTITION_NAME, &self.dependency_partition),
+            (ISSUE_PARTITION_NAME, &self.issue_partition),
+            (ISSUE_HISTORY_PARTITION_NAME, &self.issue_history_partition),
+            (OWNERSHIP_PARTITION_NAME, &self.ownership_partition),
+            (PULL_REQUEST_PARTITION_NAME, &self.pull_request_partition),
+            (
+                PULL_REQUEST_HISTORY_PARTITION_NAME,
+                &self.pull_request_history_partition,
+            ),
+            (DISCUSSION_PARTITION_NAME, &self.discussion_partition),
+            (REPOSITORY_PARTITION_NAME, &self.repository_partition),
+            (SNAPSHOT_PARTITION_NAME, &self.snapshot_partition),
+            (SYNC_STATUS_PARTITION_NAME, &self.sync_status_partition),
+            (TEAM_PARTITION_NAME, &self.team_partition),
+            (TODO_PARTITION_NAME, &self.todo_partition),
+            (
+                VULNERABILITY_ALERT_PARTITION_NAME,
+                &self.vulnerability_alert_partition,
+            ),
+        ]
+    }
+}
+
+/// Adds a version header to the values stored as plain `bincode`, converting
+/// the issues and pull requests stored with the layouts of `legacy` to the
+/// current ones.
+///
+/// The values of every partition are written in a single batch together with
+/// the new storage version, so that a migration interrupted midway leaves the
+/// database untouched instead of adding a second header on the next run.
+fn add_version_headers(db: &Database) -> Result<()> {
+    let global = db.keyspace.open_partition(
+        GLOBAL_PARTITION_NAME,
+        fjall::PartitionCreateOptions::default(),
+    )?;
+    let mut batch = db.keyspace.batch();
+    for (name, partition) in db.data_partitions() {
+        for item in partition.iter() {
+            let (key, value) = item?;
+            let value = match name {
+                ISSUE_PARTITION_NAME => convert_legacy::<LegacyIssue, GitHubIssue>(&key, &value)?,
+                PULL_REQUEST_PARTITION_NAME => {
+                    convert_legacy::<LegacyPullRequest, GitHubPullRequestNode>(&key, &value)?
+                }
+                _ => value.to_vec(),
+            };
+            let mut headed = Vec::with_capacity(VERSION_HEADER_LEN + value.len());
+            headed.extend_from_slice(&1_u16.to_be_bytes());
+            headed.extend_from_slice(&value);
+            batch.insert(partition, key, headed);
+        }
+    }
+    batch.insert(
+        &global,
+        STORAGE_VERSION_KEY,
+        bincode::serialize(&1_u16.to_string())?,
+    );
+    batch.commit()?;
+    Ok(())
+}
+
+/// Decodes a value stored with the layout `L` and encodes it with the layout
+/// `T`, without a version header.
+fn convert_legacy<L, T>(key: &[u8], value: &[u8]) -> Result<Vec<u8>>
+where
+    L: DeserializeOwned + Into<T>,
+    T: Serialize,
+{
+    let legacy: L = bincode::deserialize(value).with_context(|| {
+        format!(
+            "invalid value in database: {}",
+            String::from_utf8_lossy(key)
+        )
+    })?;
+    Ok(bincode::serialize(&legacy.into())?)
+}
+
+/// Adds the index entries of the records stored before the indexes were
+/// introduced.
+fn build_indexes(db: &Database) -> Result<()> {
+    db.build_indexes()
+}
+
+/// Replaces the `owner/name#number` keys of issues, pull requests,
+/// discussions and vulnerability alerts with the keys made by `record_key`,
+/// and rebuilds the indexes referring to them.
+fn use_numeric_record_keys(db: &Database) -> Result<()> {
+    for partition in [
+        &db.issue_partition,
+        &db.pull_request_partition,
+        &db.discussion_partition,
+        &db.vulnerability_alert_partition,
+    ] {
+        let records = partition.iter().collect::<fjall::Result<Vec<_>>>()?;
+        let mut batch = db.keyspace.batch();
+        for (key, value) in records {
+            let Ok(legacy) = std::str::from_utf8(&key) else {
+                continue;
+            };
+            let Some(captures) = LEGACY_RECORD_KEY.captures(legacy) else {
+                continue;
+            };
+            let number = captures["number"]
+                .parse()
+                .map_err(|_| anyhow!("invalid key in database: {legacy}"))?;
+            batch.insert(
+                partition,
+                record_key(&captures["owner"], &captures["name"], number),
+                value,
+            );
+            batch.remove(partition, key);
+        }
+        batch.commit()?;
+    }
+
+    db.rebuild_indexes()
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::Timestamp;
+
+    use super::{
+        add_version_headers,
+        legacy::{
+            LegacyIssue, LegacyIssueComment, LegacyIssueCommentConnection,
+            LegacyPRCommentConnection, LegacyPullRequest, LegacyReviewConnection,
+        },
+        STORAGE_VERSION,
+    };
+    use crate::{
+        database::{
+            decode, encode,
+            index::IndexFilter,
+            issue::{GitHubProjectV2ItemConnection, GitHubSubIssueConnection},
+            pull_request::{GitHubCommitConnection, GitHubPullRequestNode, RepositoryNode},
+            record_key, Database, DiscussionDbSchema, GitHubIssue,
+        },
+        outbound::{issues::IssueState, pull_requests::PullRequestState},
+    };
+
+    #[test]
+    fn migrate_unversioned_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        assert_eq!(db.storage_version().unwrap(), STORAGE_VERSION);
+
+        // Values stored before the format was versioned, with the layouts of
+        // that time.
+        let issue = LegacyIssue {
+            id: "I_1".to_string(),
+            number: 7,
+            title: "Crash on start".to_string(),
+            author: "alice".to_string(),
+            body: String::new(),
+            state: IssueState::OPEN,
+            assignees: vec!["bob".to_string()],
+            labels: Vec::new(),
+            comments: LegacyIssueCommentConnection {
+                total_count: 1,
+                nodes: vec![LegacyIssueComment {
+                    id: "IC_1".to_string(),
+                    author: "bob".to_string(),
+                    body: "Confirmed".to_string(),
+                    created_at: Timestamp::UNIX_EPOCH,
+                    updated_at: Timestamp::UNIX_EPOCH,
+                    repository_name: "frontary".to_string(),
+                    url: String::new(),
+                }],
+            },
+            project_items: GitHubProjectV2ItemConnection::default(),
+            sub_issues: GitHubSubIssueConnection::default(),
+            parent: None,
+            url: String::new(),
+            closed_by_pull_requests: Vec::new(),
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            closed_at: None,
+        };
+        db.issue_partition
+            .insert("aicers/frontary#7", bincode::serialize(&issue).unwrap())
+            .unwrap();
+        let pr = LegacyPullRequest {
+            id: "PR_1".to_string(),
+            number: 8,
+            title: "Fix crash".to_string(),
+            body: None,
+            state: PullRequestState::MERGED,
+            created_at: Timestamp::UNIX_EPOCH,
+            updated_at: Timestamp::UNIX_EPOCH,
+            closed_at: None,
+            merged_at: None,
+            author: "carol".to_string(),
+            additions: 3,
+            deletions: 1,
+            url: String::new(),
+            repository: RepositoryNode {
+                owner: "aicers".to_string(),
+                name: "frontary".to_string(),
+            },
+            labels: Vec::new(),
+            comments: LegacyPRCommentConnection {
+                total_count: 0,
+                nodes: Vec::new(),
+            },
+            review_decision: None,
+            assignees: Vec::new(),
+            review_requests: Vec::new(),
+            reviews: LegacyReviewConnection {
+                total_count: 0,
+                nodes: Vec::new(),
+            },
+            commits: GitHubCommitConnection {
+                total_count: 0,
+                nodes: Vec::new(),
+            },
+        };
+        db.pull_request_partition
+            .insert("aicers/frontary#8", bincode::serialize(&pr).unwrap())
+            .unwrap();
+        db.insert_db("storage_version", "0").unwrap();
+        db.migrate().unwrap();
+
+        assert_eq!(db.storage_version().unwrap(), STORAGE_VERSION);
+        let migrated = db.issue("aicers", "frontary", 7).unwrap().unwrap();
+        assert_eq!(migrated, GitHubIssue::from(issue));
+        assert_eq!(migrated.comments.nodes[0].body, "Confirmed");
+        let migrated: GitHubPullRequestNode = decode(
+            &db.pull_request_partition
+                .get(record_key("aicers", "frontary", 8))
+                .unwrap()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(migrated.additions, 3);
+        assert!(migrated.reactions.is_empty());
+        let issues: Vec<_> = db
+            .issues_matching(&IndexFilter {
+                authors: Some(vec!["alice"]),
+                ..Default::default()
+            })
+            .unwrap()
+            .map(|issue| issue.unwrap().number)
+            .collect();
+        assert_eq!(issues, [7]);
+    }
+
+    #[test]
+    fn bump_version_with_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        let discussion = DiscussionDbSchema {
+            number: 5,
+            ..Default::default()
+        };
+        db.discussion_partition
+            .insert(
+                "aicers/frontary#5",
+                bincode::serialize(&discussion).unwrap(),
+            )
+            .unwrap();
+        db.insert_db("storage_version", "0").unwrap();
+
+        // The storage version is written with the headers, so the migration
+        // is not run again on values that already have one.
+        add_version_headers(&db).unwrap();
+        assert_eq!(db.storage_version().unwrap(), 1);
+        db.migrate().unwrap();
+        let numbers: Vec<_> = db
+            .discussions(None, None)
+            .map(|discussion| discussion.unwrap().number)
+            .collect();
+        assert_eq!(numbers, [5]);
+    }
+
+    #[test]
+    fn migrate_legacy_record_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        for number in [9, 10] {
+            let issue = GitHubIssue {
+                number,
+                ..Default::default()
+            };
+            db.issue_partition
+                .insert(format!("aicers/frontary#{number}"), encode(&issue).unwrap())
+                .unwrap();
+        }
+        db.insert_db("storage_version", "2").unwrap();
+        db.migrate().unwrap();
+
+        let numbers: Vec<_> = db
+            .issues(None, None)
+            .map(|issue| issue.unwrap().number)
+            .collect();
+        assert_eq!(numbers, [9, 10]);
+        assert!(db.issue("aicers", "frontary", 10).unwrap().is_some());
+    }
+
+    #[test]
+    fn reject_newer_storage_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        db.insert_db("storage_version", (STORAGE_VERSION + 1).to_string())
+            .unwrap();
+        assert!(db.migrate().is_err());
+    }
+
+    #[test]
+    fn reject_invalid_storage_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::connect(dir.path()).unwrap();
+        db.insert_db("storage_version", "three").unwrap();
+        assert!(db.storage_version().is_err());
+        assert!(db.migrate().is_err());
+    }
+}
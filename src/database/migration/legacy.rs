@@ -0,0 +1,239 @@
+//! The layouts of the values stored before the storage format was versioned.
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    database::{
+        issue::{
+            GitHubIssueComment, GitHubIssueCommentConnection, GitHubParentIssue,
+            GitHubProjectV2ItemConnection, GitHubPullRequestRef, GitHubSubIssueConnection,
+        },
+        pull_request::{
+            GitHubCommitConnection, GitHubPRComment, GitHubPRCommentConnection,
+            GitHubPullRequestNode, GitHubReviewConnection, RepositoryNode, ReviewNode,
+        },
+        GitHubIssue,
+    },
+    outbound::{
+        issues::IssueState,
+        pull_requests::{MergeableState, PullRequestReviewState, PullRequestState},
+    },
+};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyIssue {
+    pub(super) id: String,
+    pub(super) number: i32,
+    pub(super) title: String,
+    pub(super) author: String,
+    pub(super) body: String,
+    pub(super) state: IssueState,
+    pub(super) assignees: Vec<String>,
+    pub(super) labels: Vec<String>,
+    pub(super) comments: LegacyIssueCommentConnection,
+    pub(super) project_items: GitHubProjectV2ItemConnection,
+    pub(super) sub_issues: GitHubSubIssueConnection,
+    pub(super) parent: Option<GitHubParentIssue>,
+    pub(super) url: String,
+    pub(super) closed_by_pull_requests: Vec<GitHubPullRequestRef>,
+    pub(super) created_at: Timestamp,
+    pub(super) updated_at: Timestamp,
+    pub(super) closed_at: Option<Timestamp>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyIssueCommentConnection {
+    pub(super) total_count: i32,
+    pub(super) nodes: Vec<LegacyIssueComment>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyIssueComment {
+    pub(super) id: String,
+    pub(super) author: String,
+    pub(super) body: String,
+    pub(super) created_at: Timestamp,
+    pub(super) updated_at: Timestamp,
+    pub(super) repository_name: String,
+    pub(super) url: String,
+}
+
+impl From<LegacyIssue> for GitHubIssue {
+    fn from(issue: LegacyIssue) -> Self {
+        Self {
+            id: issue.id,
+            number: issue.number,
+            title: issue.title,
+            author: issue.author,
+            body: issue.body,
+            state: issue.state,
+            assignees: issue.assignees,
+            labels: issue.labels,
+            comments: GitHubIssueCommentConnection {
+                total_count: issue.comments.total_count,
+                nodes: issue
+                    .comments
+                    .nodes
+                    .into_iter()
+                    .map(|comment| GitHubIssueComment {
+                        id: comment.id,
+                        author: comment.author,
+                        body: comment.body,
+                        created_at: comment.created_at,
+                        updated_at: comment.updated_at,
+                        repository_name: comment.repository_name,
+                        url: comment.url,
+                        reactions: Vec::new(),
+                    })
+                    .collect(),
+            },
+            project_items: issue.project_items,
+            sub_issues: issue.sub_issues,
+            parent: issue.parent,
+            url: issue.url,
+            closed_by_pull_requests: issue.closed_by_pull_requests,
+            reactions: Vec::new(),
+            created_at: issue.created_at,
+            updated_at: issue.updated_at,
+            closed_at: issue.closed_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyPullRequest {
+    pub(super) id: String,
+    pub(super) number: i32,
+    pub(super) title: String,
+    pub(super) body: Option<String>,
+    pub(super) state: PullRequestState,
+    pub(super) created_at: Timestamp,
+    pub(super) updated_at: Timestamp,
+    pub(super) closed_at: Option<Timestamp>,
+    pub(super) merged_at: Option<Timestamp>,
+    pub(super) author: String,
+    pub(super) additions: i32,
+    pub(super) deletions: i32,
+    pub(super) url: String,
+    pub(super) repository: RepositoryNode,
+    pub(super) labels: Vec<String>,
+    pub(super) comments: LegacyPRCommentConnection,
+    pub(super) review_decision: Option<PullRequestReviewState>,
+    pub(super) assignees: Vec<String>,
+    pub(super) review_requests: Vec<String>,
+    pub(super) reviews: LegacyReviewConnection,
+    pub(super) commits: GitHubCommitConnection,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyPRCommentConnection {
+    pub(super) total_count: i32,
+    pub(super) nodes: Vec<LegacyPRComment>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyPRComment {
+    pub(super) author: String,
+    pub(super) body: String,
+    pub(super) created_at: Timestamp,
+    pub(super) updated_at: Timestamp,
+    pub(super) repository_name: String,
+    pub(super) url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyReviewConnection {
+    pub(super) total_count: i32,
+    pub(super) nodes: Vec<LegacyReviewNode>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(super) struct LegacyReviewNode {
+    pub(super) author: String,
+    pub(super) state: PullRequestReviewState,
+    pub(super) body: Option<String>,
+    pub(super) url: String,
+    pub(super) created_at: Timestamp,
+    pub(super) published_at: Option<Timestamp>,
+    pub(super) submitted_at: Timestamp,
+    pub(super) is_minimized: bool,
+    pub(super) comments: LegacyPRCommentConnection,
+}
+
+impl From<LegacyPRCommentConnection> for GitHubPRCommentConnection {
+    fn from(comments: LegacyPRCommentConnection) -> Self {
+        Self {
+            total_count: comments.total_count,
+            nodes: comments
+                .nodes
+                .into_iter()
+                .map(|comment| GitHubPRComment {
+                    author: comment.author,
+                    body: comment.body,
+                    created_at: comment.created_at,
+                    updated_at: comment.updated_at,
+                    repository_name: comment.repository_name,
+                    url: comment.url,
+                    reactions: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<LegacyPullRequest> for GitHubPullRequestNode {
+    fn from(pr: LegacyPullRequest) -> Self {
+        Self {
+            id: pr.id,
+            number: pr.number,
+            title: pr.title,
+            body: pr.body,
+            state: pr.state,
+            created_at: pr.created_at,
+            updated_at: pr.updated_at,
+            closed_at: pr.closed_at,
+            merged_at: pr.merged_at,
+            author: pr.author,
+            is_draft: false,
+            base_ref_name: String::new(),
+            head_ref_name: String::new(),
+            mergeable: MergeableState::UNKNOWN,
+            merged_by: None,
+            additions: pr.additions,
+            deletions: pr.deletions,
+            changed_files: 0,
+            files: Vec::new(),
+            url: pr.url,
+            milestone: None,
+            closing_issues: Vec::new(),
+            repository: pr.repository,
+            labels: pr.labels,
+            comments: pr.comments.into(),
+            reactions: Vec::new(),
+            review_decision: pr.review_decision,
+            assignees: pr.assignees,
+            review_requests: pr.review_requests,
+            reviews: GitHubReviewConnection {
+                total_count: pr.reviews.total_count,
+                nodes: pr
+                    .reviews
+                    .nodes
+                    .into_iter()
+                    .map(|review| ReviewNode {
+                        author: review.author,
+                        state: review.state,
+                        body: review.body,
+                        url: review.url,
+                        created_at: review.created_at,
+                        published_at: review.published_at,
+                        submitted_at: review.submitted_at,
+                        is_minimized: review.is_minimized,
+                        comments: review.comments.into(),
+                    })
+                    .collect(),
+            },
+            commits: pr.commits,
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    pub(crate) fn insert_file_ownership(
+        &self,
+        ownership: FileOwnership,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        let keystr = format!("{owner}/{name}/{}", ownership.path);
+        Database::insert(&keystr, ownership, &self.ownership_partition)
+    }
+
+    pub(crate) fn remove_file_ownership(&self, owner: &str, name: &str, path: &str) -> Result<()> {
+        self.ownership_partition
+            .remove(format!("{owner}/{name}/{path}"))?;
+        Ok(())
+    }
+
+    /// Removes the ownership of every file of the repository.
+    pub(crate) fn clear_ownership(&self, owner: &str, name: &str) -> Result<()> {
+        for item in self.ownership_partition.prefix(format!("{owner}/{name}/")) {
+            let (key, _) = item?;
+            self.ownership_partition.remove(key)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the ownership of the files whose path starts with `path_prefix`,
+    /// ordered by path.
+    pub(crate) fn file_ownerships(
+        &self,
+        owner: &str,
+        name: &str,
+        path_prefix: &str,
+    ) -> Result<Vec<FileOwnership>> {
+        self.ownership_partition
+            .prefix(format!("{owner}/{name}/{path_prefix}"))
+            .map(|item| {
+                let (_, value) = item?;
+                super::decode(&value)
+            })
+            .collect()
+    }
+
+    /// Returns the commit the stored ownership was computed at, if any.
+    pub(crate) fn ownership_head(&self, owner: &str, name: &str) -> Option<String> {
+        self.select_db(&format!("ownership_head/{owner}/{name}"))
+            .ok()
+    }
+
+    pub(crate) fn insert_ownership_head(&self, owner: &str, name: &str, oid: &str) -> Result<()> {
+        self.insert_db(&format!("ownership_head/{owner}/{name}"), oid)
+    }
+}
+
+/// The authors of the lines of a file, as reported by `git blame`.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct FileOwnership {
+    pub(crate) path: String,
+    /// The number of lines last changed by each author, most lines first.
+    pub(crate) authors: Vec<AuthorLines>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct AuthorLines {
+    pub(crate) author: String,
+    pub(crate) lines: usize,
+}
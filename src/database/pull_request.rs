@@ -1,10 +1,20 @@
-use anyhow::Result;
+use anyhow::{Error, Result};
 use jiff::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use super::{Database, Iter};
+use super::{
+    history,
+    reaction::{self, GitHubReactionGroup},
+    Database, Iter,
+};
 use crate::api::pull_request::PullRequest;
-use crate::outbound::pull_requests::{PullRequestReviewState, PullRequestState};
+use crate::outbound::pull_requests::{
+    MergeableState, PullRequestReviewState, PullRequestState,
+    PullRequestsRepositoryPullRequestsNodesCommentsNodesReactionGroups,
+    PullRequestsRepositoryPullRequestsNodesCommentsNodesReactionGroupsReactorsNodes::User as CommentReactorUser,
+    PullRequestsRepositoryPullRequestsNodesReactionGroups,
+    PullRequestsRepositoryPullRequestsNodesReactionGroupsReactorsNodes::User as PullRequestReactorUser,
+};
 
 impl Database {
     pub(crate) fn insert_pull_requests(
@@ -13,9 +23,28 @@ impl Database {
         owner: &str,
         name: &str,
     ) -> Result<()> {
+        let synced_at = Timestamp::now();
         for item in resp {
-            let keystr: String = format!("{owner}/{name}#{}", item.number);
-            Database::insert(&keystr, item, &self.pull_request_partition)?;
+            let key = super::record_key(owner, name, item.number);
+            let mut batch = self.keyspace.batch();
+            let old = Self::insert_indexed(
+                &mut batch,
+                &key,
+                &item,
+                &self.pull_request_partition,
+                &self.pull_request_index_partition,
+            )?;
+            if let Some(old) = old {
+                history::insert_changes(
+                    &mut batch,
+                    &old,
+                    &item,
+                    &key,
+                    synced_at,
+                    &self.pull_request_history_partition,
+                )?;
+            }
+            batch.commit()?;
         }
         Ok(())
     }
@@ -42,6 +71,7 @@ pub struct GitHubPRComment {
     pub(crate) updated_at: Timestamp,
     pub(crate) repository_name: String,
     pub(crate) url: String,
+    pub(crate) reactions: Vec<GitHubReactionGroup>,
 }
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -68,6 +98,13 @@ pub struct RepositoryNode {
     pub(crate) name: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosingIssueNode {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReviewNode {
     pub(crate) author: String,
@@ -105,15 +142,74 @@ pub struct GitHubPullRequestNode {
     pub(crate) closed_at: Option<Timestamp>,
     pub(crate) merged_at: Option<Timestamp>,
     pub(crate) author: String,
+    pub(crate) is_draft: bool,
+    pub(crate) base_ref_name: String,
+    pub(crate) head_ref_name: String,
+    pub(crate) mergeable: MergeableState,
+    pub(crate) merged_by: Option<String>,
     pub(crate) additions: i32,
     pub(crate) deletions: i32,
+    pub(crate) changed_files: i32,
+    /// The paths of the changed files.
+    pub(crate) files: Vec<String>,
     pub(crate) url: String,
+    pub(crate) milestone: Option<String>,
+    pub(crate) closing_issues: Vec<ClosingIssueNode>,
     pub(crate) repository: RepositoryNode,
     pub(crate) labels: Vec<String>,
     pub(crate) comments: GitHubPRCommentConnection,
+    pub(crate) reactions: Vec<GitHubReactionGroup>,
     pub(crate) review_decision: Option<PullRequestReviewState>,
     pub(crate) assignees: Vec<String>,
     pub(crate) review_requests: Vec<String>,
     pub(crate) reviews: GitHubReviewConnection,
     pub(crate) commits: GitHubCommitConnection,
 }
+
+impl TryFrom<&PullRequestsRepositoryPullRequestsNodesReactionGroups> for GitHubReactionGroup {
+    type Error = Error;
+
+    fn try_from(group: &PullRequestsRepositoryPullRequestsNodesReactionGroups) -> Result<Self> {
+        Ok(Self {
+            content: reaction::content_name(&group.content),
+            count: group.reactors.total_count.try_into()?,
+            users: group
+                .reactors
+                .nodes
+                .iter()
+                .flatten()
+                .flatten()
+                .filter_map(|reactor| match reactor {
+                    PullRequestReactorUser(u) => Some(u.login.clone()),
+                    _ => None,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<&PullRequestsRepositoryPullRequestsNodesCommentsNodesReactionGroups>
+    for GitHubReactionGroup
+{
+    type Error = Error;
+
+    fn try_from(
+        group: &PullRequestsRepositoryPullRequestsNodesCommentsNodesReactionGroups,
+    ) -> Result<Self> {
+        Ok(Self {
+            content: reaction::content_name(&group.content),
+            count: group.reactors.total_count.try_into()?,
+            users: group
+                .reactors
+                .nodes
+                .iter()
+                .flatten()
+                .flatten()
+                .filter_map(|reactor| match reactor {
+                    CommentReactorUser(u) => Some(u.login.clone()),
+                    _ => None,
+                })
+                .collect(),
+        })
+    }
+}
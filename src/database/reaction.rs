@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The reactions of a single emoji on an issue, a pull request or a comment.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitHubReactionGroup {
+    /// The emoji, as named by GitHub (e.g. `THUMBS_UP`).
+    pub(crate) content: String,
+    pub(crate) count: i32,
+    /// Logins of the users who reacted. Bots and organizations are omitted.
+    pub(crate) users: Vec<String>,
+}
+
+/// Returns the GitHub name of a `ReactionContent` generated for any query.
+///
+/// Each GraphQL query module has its own `ReactionContent` enum, and all of
+/// them serialize to the variant name used by GitHub.
+pub(crate) fn content_name<T: Serialize>(content: &T) -> String {
+    match serde_json::to_value(content) {
+        Ok(Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::{Error, Result};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+use crate::outbound::repository_metadata::{
+    RepositoryMetadataRepository, RepositoryMetadataRepositoryLanguages,
+};
+
+impl Database {
+    /// Stores a snapshot of the repository metadata.
+    ///
+    /// Snapshots are keyed by the UTC date they were fetched on, so only the
+    /// last snapshot of each day is kept.
+    pub(crate) fn insert_repository_snapshot(
+        &self,
+        snapshot: GitHubRepositorySnapshot,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        let keystr = format!(
+            "{owner}/{name}/{}",
+            snapshot.fetched_at.strftime("%Y-%m-%d")
+        );
+        Database::insert(&keystr, snapshot, &self.repository_partition)
+    }
+
+    /// Returns the snapshots of the repository metadata, oldest first.
+    pub(crate) fn repository_snapshots(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<Vec<GitHubRepositorySnapshot>> {
+        self.repository_partition
+            .prefix(format!("{owner}/{name}/"))
+            .map(|item| {
+                let (_, value) = item?;
+                super::decode(&value)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitHubRepositorySnapshot {
+    pub(crate) fetched_at: Timestamp,
+    pub(crate) stargazer_count: i32,
+    pub(crate) fork_count: i32,
+    pub(crate) watcher_count: i32,
+    pub(crate) primary_language: Option<String>,
+    pub(crate) languages: Vec<GitHubLanguageSize>,
+    pub(crate) default_branch: Option<String>,
+    pub(crate) is_archived: bool,
+    pub(crate) open_issue_count: i32,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitHubLanguageSize {
+    pub(crate) name: String,
+    /// The number of bytes of code written in the language.
+    pub(crate) size: i64,
+}
+
+/// Convert the *Repository* of GitHub GraphQL API to a snapshot taken at `Timestamp::now()`.
+impl TryFrom<RepositoryMetadataRepository> for GitHubRepositorySnapshot {
+    type Error = Error;
+
+    fn try_from(repo: RepositoryMetadataRepository) -> Result<Self> {
+        Ok(Self {
+            fetched_at: Timestamp::now(),
+            stargazer_count: repo.stargazer_count.try_into()?,
+            fork_count: repo.fork_count.try_into()?,
+            watcher_count: repo.watchers.total_count.try_into()?,
+            primary_language: repo.primary_language.map(|language| language.name),
+            languages: repo.languages.map(Vec::from).unwrap_or_default(),
+            default_branch: repo.default_branch_ref.map(|branch| branch.name),
+            is_archived: repo.is_archived,
+            open_issue_count: repo.issues.total_count.try_into()?,
+        })
+    }
+}
+
+impl From<RepositoryMetadataRepositoryLanguages> for Vec<GitHubLanguageSize> {
+    fn from(languages: RepositoryMetadataRepositoryLanguages) -> Self {
+        languages
+            .edges
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+            .map(|edge| GitHubLanguageSize {
+                name: edge.node.name,
+                size: edge.size,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, ops::Bound};
+
+use anyhow::{anyhow, Result};
+use jiff::{civil::Date, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use super::{pull_request::GitHubPullRequestNode, record_prefix, Database, GitHubIssue};
+
+impl Database {
+    /// Stores the counts of the issues and pull requests of the repository.
+    ///
+    /// Snapshots are keyed by the UTC date they were taken on, so only the
+    /// last snapshot of each day is kept.
+    pub(crate) fn insert_daily_snapshot(
+        &self,
+        owner: &str,
+        name: &str,
+        taken_at: Timestamp,
+    ) -> Result<()> {
+        let mut issues: BTreeMap<String, StateSnapshot> = BTreeMap::new();
+        for item in self.issue_partition.prefix(record_prefix(owner, name)) {
+            let (_, value) = item?;
+            let issue: GitHubIssue = super::decode(&value)?;
+            let statuses = issue
+                .project_items
+                .nodes
+                .iter()
+                .filter_map(|item| item.todo_status.as_deref());
+            issues.entry(format!("{:?}", issue.state)).or_default().add(
+                &issue.labels,
+                &issue.assignees,
+                statuses,
+            );
+        }
+        let mut pull_requests: BTreeMap<String, StateSnapshot> = BTreeMap::new();
+        for item in self
+            .pull_request_partition
+            .prefix(record_prefix(owner, name))
+        {
+            let (_, value) = item?;
+            let pr: GitHubPullRequestNode = super::decode(&value)?;
+            pull_requests
+                .entry(format!("{:?}", pr.state))
+                .or_default()
+                .add(&pr.labels, &pr.assignees, std::iter::empty());
+        }
+
+        let snapshot = DailySnapshot {
+            owner: owner.to_string(),
+            repo: name.to_string(),
+            issues,
+            pull_requests,
+        };
+        let keystr = format!("{}/{owner}/{name}", taken_at.strftime("%Y-%m-%d"));
+        Database::insert(&keystr, snapshot, &self.snapshot_partition)
+    }
+
+    /// Returns the snapshots taken on the dates within `[begin, end)`, oldest
+    /// first, with the dates they were taken on.
+    pub(crate) fn daily_snapshots(
+        &self,
+        begin: Option<Date>,
+        end: Option<Date>,
+    ) -> Result<Vec<(Date, DailySnapshot)>> {
+        let begin = begin.map_or(Bound::Unbounded, |begin| Bound::Included(begin.to_string()));
+        let end = end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_string()));
+        self.snapshot_partition
+            .range((begin, end))
+            .map(|item| {
+                let (key, value) = item?;
+                let date = std::str::from_utf8(&key)
+                    .ok()
+                    .and_then(|key| key.split_once('/'))
+                    .and_then(|(date, _)| date.parse().ok())
+                    .ok_or_else(|| anyhow!("invalid key in database: {key:02x?}"))?;
+                Ok((date, super::decode(&value)?))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct DailySnapshot {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    /// The counts of the issues, keyed by state.
+    pub(crate) issues: BTreeMap<String, StateSnapshot>,
+    /// The counts of the pull requests, keyed by state.
+    pub(crate) pull_requests: BTreeMap<String, StateSnapshot>,
+}
+
+/// The counts of the records in a state. A record with several labels,
+/// assignees or project statuses is counted under each of them.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct StateSnapshot {
+    pub(crate) count: usize,
+    pub(crate) labels: BTreeMap<String, usize>,
+    pub(crate) assignees: BTreeMap<String, usize>,
+    pub(crate) project_statuses: BTreeMap<String, usize>,
+}
+
+impl StateSnapshot {
+    fn add<'a>(
+        &mut self,
+        labels: &[String],
+        assignees: &[String],
+        project_statuses: impl Iterator<Item = &'a str>,
+    ) {
+        self.count += 1;
+        for label in labels {
+            *self.labels.entry(label.clone()).or_default() += 1;
+        }
+        for assignee in assignees {
+            *self.assignees.entry(assignee.clone()).or_default() += 1;
+        }
+        for status in project_statuses {
+            *self.project_statuses.entry(status.to_string()).or_default() += 1;
+        }
+    }
+}
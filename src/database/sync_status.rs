@@ -0,0 +1,56 @@
+use anyhow::Result;
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    /// Stores the result of the latest checkout sync of the repository.
+    pub(crate) fn insert_sync_status(
+        &self,
+        status: SyncStatus,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        Database::insert(
+            format!("{owner}/{name}"),
+            status,
+            &self.sync_status_partition,
+        )
+    }
+
+    /// Returns the result of the latest checkout sync of the repository, if
+    /// it has been synced at least once.
+    pub(crate) fn sync_status(&self, owner: &str, name: &str) -> Result<Option<SyncStatus>> {
+        self.sync_status_partition
+            .get(format!("{owner}/{name}"))?
+            .map(|value| super::decode(&value))
+            .transpose()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct SyncStatus {
+    pub(crate) synced_at: Timestamp,
+    /// The branch checked out in the local clone.
+    pub(crate) branch: String,
+    /// The commit the branch points to after the sync.
+    pub(crate) head: String,
+    pub(crate) event: SyncEvent,
+}
+
+/// What the sync did to the local clone.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub(crate) enum SyncEvent {
+    /// The clone already had the latest commit.
+    #[default]
+    UpToDate,
+    /// The branch was fast-forwarded to the latest commit.
+    FastForwarded,
+    /// The clone was switched to another branch.
+    BranchSwitched,
+    /// The upstream history diverged, so the branch was hard-reset to it.
+    Reset,
+    /// The clone was corrupted, so it was cloned again.
+    Recloned,
+}
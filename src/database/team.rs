@@ -0,0 +1,56 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{Database, Iter};
+use crate::api::team::Team;
+use crate::outbound::teams::TeamsOrganizationTeamsNodes;
+
+impl Database {
+    /// Replaces the stored teams of the organization with `resp`.
+    pub(crate) fn insert_teams(&self, resp: Vec<GitHubTeam>, organization: &str) -> Result<()> {
+        let prefix = format!("{organization}/");
+        let keys = self
+            .team_partition
+            .prefix(&prefix)
+            .map(|item| item.map(|(key, _)| key))
+            .collect::<fjall::Result<Vec<_>>>()?;
+        let mut batch = self.keyspace.batch();
+        for key in keys {
+            batch.remove(&self.team_partition, key);
+        }
+        for item in resp {
+            let keystr = format!("{prefix}{}", item.slug);
+            batch.insert(&self.team_partition, keystr, super::encode(&item)?);
+        }
+        batch.commit()?;
+        Ok(())
+    }
+
+    pub(crate) fn teams(&self) -> Iter<Team> {
+        Iter::new(self.team_partition.iter())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GitHubTeam {
+    pub(crate) slug: String,
+    pub(crate) name: String,
+    pub(crate) members: Vec<String>,
+}
+
+impl From<TeamsOrganizationTeamsNodes> for GitHubTeam {
+    fn from(team: TeamsOrganizationTeamsNodes) -> Self {
+        Self {
+            slug: team.slug,
+            name: team.name,
+            members: team
+                .members
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|user| user.login)
+                .collect(),
+        }
+    }
+}
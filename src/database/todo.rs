@@ -0,0 +1,55 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+impl Database {
+    /// Stores the TODO markers of the repository, replacing the previously
+    /// stored ones.
+    pub(crate) fn insert_code_todos(
+        &self,
+        todos: Vec<CodeTodo>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        Database::insert(format!("{owner}/{name}"), todos, &self.todo_partition)
+    }
+
+    /// Returns the TODO markers of the repository, ordered by path and line.
+    pub(crate) fn code_todos(&self, owner: &str, name: &str) -> Result<Vec<CodeTodo>> {
+        Ok(self
+            .todo_partition
+            .get(format!("{owner}/{name}"))?
+            .map(|value| super::decode(&value))
+            .transpose()?
+            .unwrap_or_default())
+    }
+}
+
+/// A `TODO`, `FIXME` or `XXX` marker in a file of a repository.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CodeTodo {
+    pub(crate) path: String,
+    /// The 1-based line number of the marker.
+    pub(crate) line: usize,
+    pub(crate) marker: TodoMarker,
+    /// The line containing the marker, trimmed.
+    pub(crate) text: String,
+    /// The issue the marker refers to with `#N` or `owner/repo#N`, if any.
+    pub(crate) issue: Option<IssueReference>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq)]
+pub(crate) enum TodoMarker {
+    #[default]
+    Todo,
+    Fixme,
+    Xxx,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+pub(crate) struct IssueReference {
+    pub(crate) owner: String,
+    pub(crate) repo: String,
+    pub(crate) number: i32,
+}
@@ -0,0 +1,76 @@
+use anyhow::{Error, Result};
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::{Database, Iter};
+use crate::api::vulnerability_alert::VulnerabilityAlert;
+use crate::outbound::vulnerability_alerts::{
+    RepositoryVulnerabilityAlertState, SecurityAdvisorySeverity,
+    VulnerabilityAlertsRepositoryVulnerabilityAlertsNodes,
+};
+
+impl Database {
+    pub(crate) fn insert_vulnerability_alerts(
+        &self,
+        resp: Vec<GitHubVulnerabilityAlert>,
+        owner: &str,
+        name: &str,
+    ) -> Result<()> {
+        for item in resp {
+            let key = super::record_key(owner, name, item.number);
+            Database::insert(&key, item, &self.vulnerability_alert_partition)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn vulnerability_alerts(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+    ) -> Iter<VulnerabilityAlert> {
+        let start = start.unwrap_or(b"\x00");
+        if let Some(end) = end {
+            Iter::new(self.vulnerability_alert_partition.range(start..end))
+        } else {
+            Iter::new(self.vulnerability_alert_partition.range(start..))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct GitHubVulnerabilityAlert {
+    pub(crate) number: i32,
+    /// The name of the vulnerable package.
+    pub(crate) package: String,
+    pub(crate) severity: SecurityAdvisorySeverity,
+    pub(crate) state: RepositoryVulnerabilityAlertState,
+    pub(crate) created_at: Timestamp,
+    pub(crate) fixed_at: Option<Timestamp>,
+    pub(crate) dismissed_at: Option<Timestamp>,
+}
+
+impl TryFrom<VulnerabilityAlertsRepositoryVulnerabilityAlertsNodes> for GitHubVulnerabilityAlert {
+    type Error = Error;
+
+    fn try_from(alert: VulnerabilityAlertsRepositoryVulnerabilityAlertsNodes) -> Result<Self> {
+        // An alert without a vulnerability is counted as of unknown severity.
+        let (package, severity) = alert.security_vulnerability.map_or_else(
+            || {
+                (
+                    String::new(),
+                    SecurityAdvisorySeverity::Other(String::new()),
+                )
+            },
+            |vulnerability| (vulnerability.package.name, vulnerability.severity),
+        );
+        Ok(Self {
+            number: alert.number.try_into()?,
+            package,
+            severity,
+            state: alert.state,
+            created_at: alert.created_at,
+            fixed_at: alert.fixed_at,
+            dismissed_at: alert.dismissed_at,
+        })
+    }
+}
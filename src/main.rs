@@ -6,14 +6,19 @@ mod outbound;
 mod settings;
 mod web;
 
-use std::sync::Arc;
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    sync::Arc,
+};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
 use database::Database;
 use google::check_key;
-use settings::{Args, Settings};
+use settings::{Args, Command, Settings};
 use tokio::{task, time};
+use tracing::info;
 
 const FIVE_MIN: u64 = 60 * 5;
 const ONE_HOUR: u64 = 60 * 60;
@@ -27,21 +32,52 @@ async fn main() -> Result<()> {
         .context("Failed to parse config file, Please check file contents")?;
 
     let repositories = Arc::new(settings.repositories);
+    let teams = Arc::new(settings.teams);
 
-    let database = Database::connect(&settings.database.db_path)
-        .context("Problem while Connect Fjall Database.")?;
+    tracing_subscriber::fmt::init();
+
+    let database = Database::connect(&settings.database.db_path).with_context(|| {
+        if matches!(args.command, Some(Command::Export { .. })) {
+            format!(
+                "Problem while Connect Fjall Database. A running server serves an export at \
+                 https://{}/export.",
+                settings.web.address
+            )
+        } else {
+            "Problem while Connect Fjall Database.".to_string()
+        }
+    })?;
+
+    match args.command {
+        Some(Command::Export { path }) => {
+            let file = File::create(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let count = database.export(BufWriter::new(file))?;
+            info!("Exported {count} entries to {}", path.display());
+            return Ok(());
+        }
+        Some(Command::Import { path }) => {
+            let file =
+                File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+            let count = database.import(BufReader::new(file))?;
+            info!("Imported {count} entries from {}", path.display());
+            return Ok(());
+        }
+        None => {}
+    }
+    let (Some(key), Some(cert)) = (args.key, args.cert) else {
+        bail!("--cert and --key are required to run the server");
+    };
 
     check_key(&database.clone())
         .await
         .context("Problem while checking for public Google key.")?;
 
-    tracing_subscriber::fmt::init();
-
     // Fetches issues and pull requests from GitHub every hour, and stores them
     // in the database.
     task::spawn(outbound::fetch_periodically(
         Arc::clone(&repositories),
-        settings.certification.token,
+        settings.certification.token.clone(),
         time::Duration::from_secs(ONE_HOUR),
         time::Duration::from_secs(FIVE_MIN),
         database.clone(),
@@ -50,11 +86,13 @@ async fn main() -> Result<()> {
     task::spawn(checkout::fetch_periodically(
         Arc::clone(&repositories),
         time::Duration::from_secs(ONE_DAY),
-        settings.certification.ssh,
+        settings.certification,
+        settings.checkout,
+        database.clone(),
     ));
 
-    let schema = api::schema(database);
+    let schema = api::schema(database.clone(), repositories, teams);
 
-    web::serve(schema, settings.web.address, &args.key, &args.cert).await;
+    web::serve(schema, database, settings.web.address, &key, &cert).await;
     Ok(())
 }
@@ -3,19 +3,25 @@ use std::{sync::Arc, time::Duration};
 use anyhow::{bail, Context, Error, Result};
 use graphql_client::{GraphQLQuery, QueryBody, Response as GraphQlResponse};
 use jiff::Timestamp;
-use reqwest::{Client, RequestBuilder, Response};
-use serde::Serialize;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
 use tokio::time;
-use tracing::error;
+use tracing::{error, warn};
 
 use crate::database::DiscussionDbSchema;
 use crate::{
     database::{
+        code_scanning_alert::{CodeScanningAlertState, GitHubCodeScanningAlert},
         issue::GitHubIssue,
         pull_request::{
-            CommitInner, GitHubCommitConnection, GitHubPRComment, GitHubPRCommentConnection,
-            GitHubPullRequestNode, GitHubReviewConnection, RepositoryNode, ReviewNode,
+            ClosingIssueNode, CommitInner, GitHubCommitConnection, GitHubPRComment,
+            GitHubPRCommentConnection, GitHubPullRequestNode, GitHubReviewConnection,
+            RepositoryNode, ReviewNode,
         },
+        reaction::GitHubReactionGroup,
+        repository::GitHubRepositorySnapshot,
+        team::GitHubTeam,
+        vulnerability_alert::GitHubVulnerabilityAlert,
         Database,
     },
     outbound::{
@@ -24,6 +30,7 @@ use crate::{
             PullRequestReviewDecision, PullRequestReviewState,
             PullRequestsRepositoryPullRequestsNodesAuthor::User as PullRequestAuthorUser,
             PullRequestsRepositoryPullRequestsNodesCommentsNodesAuthor as PRCommentAuthor,
+            PullRequestsRepositoryPullRequestsNodesMergedBy::User as PullRequestMergedByUser,
             PullRequestsRepositoryPullRequestsNodesReviewRequestsNodesRequestedReviewer::User as PRReviewRequestedUser,
         },
     },
@@ -32,6 +39,10 @@ use crate::{
 
 const GITHUB_FETCH_SIZE: i64 = 10;
 const GITHUB_URL: &str = "https://api.github.com/graphql";
+const GITHUB_REST_URL: &str = "https://api.github.com";
+/// The number of items fetched in a page from the GitHub REST API, which
+/// allows up to 100.
+const GITHUB_REST_PAGE_SIZE: usize = 100;
 const APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 const INIT_TIME: &str = "1992-06-05T00:00:00Z";
 
@@ -64,6 +75,62 @@ pub(crate) struct PullRequests;
 )]
 pub(crate) struct Discussions;
 
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/outbound/graphql/schema.graphql",
+    query_path = "src/outbound/graphql/repository_metadata.graphql",
+    response_derives = "Debug"
+)]
+pub(crate) struct RepositoryMetadata;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/outbound/graphql/schema.graphql",
+    query_path = "src/outbound/graphql/teams.graphql",
+    response_derives = "Debug"
+)]
+pub(crate) struct Teams;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/outbound/graphql/schema.graphql",
+    query_path = "src/outbound/graphql/team_members.graphql",
+    response_derives = "Debug"
+)]
+pub(crate) struct TeamMembers;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "src/outbound/graphql/schema.graphql",
+    query_path = "src/outbound/graphql/vulnerability_alerts.graphql",
+    response_derives = "Debug"
+)]
+pub(crate) struct VulnerabilityAlerts;
+
+/// A code scanning alert in a response of the GitHub REST API, which is the
+/// only API exposing them.
+#[derive(Debug, Deserialize)]
+pub(crate) struct CodeScanningAlertNode {
+    pub(crate) number: i64,
+    pub(crate) state: CodeScanningAlertState,
+    pub(crate) created_at: Timestamp,
+    pub(crate) fixed_at: Option<Timestamp>,
+    pub(crate) dismissed_at: Option<Timestamp>,
+    pub(crate) rule: CodeScanningRule,
+    pub(crate) tool: CodeScanningTool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CodeScanningRule {
+    pub(crate) id: Option<String>,
+    pub(crate) security_severity_level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct CodeScanningTool {
+    pub(crate) name: Option<String>,
+}
+
 #[allow(clippy::derivable_impls)]
 impl Default for IssueState {
     fn default() -> Self {
@@ -71,6 +138,7 @@ impl Default for IssueState {
     }
 }
 
+#[allow(clippy::too_many_lines)]
 pub(super) async fn fetch_periodically(
     repositories: Arc<Vec<RepoInfo>>,
     token: String,
@@ -146,6 +214,98 @@ pub(super) async fn fetch_periodically(
                 }
                 itv.reset();
             }
+
+            let mut re_itv = time::interval(retry);
+            loop {
+                re_itv.tick().await;
+                match send_github_repository_query(&repoinfo.owner, &repoinfo.name, &token).await {
+                    Ok(snapshot) => {
+                        if let Err(error) =
+                            db.insert_repository_snapshot(snapshot, &repoinfo.owner, &repoinfo.name)
+                        {
+                            error!("Problem while insert Fjall Database. {}", error);
+                        }
+                        break;
+                    }
+                    Err(error) => {
+                        error!("Problem while sending github repository query. Query retransmission is done after 5 minutes. {}", error);
+                    }
+                }
+                itv.reset();
+            }
+
+            let mut re_itv = time::interval(retry);
+            loop {
+                re_itv.tick().await;
+                match send_github_vulnerability_alert_query(&repoinfo.owner, &repoinfo.name, &token)
+                    .await
+                {
+                    Ok(resps) => {
+                        if let Err(error) =
+                            db.insert_vulnerability_alerts(resps, &repoinfo.owner, &repoinfo.name)
+                        {
+                            error!("Problem while insert Fjall Database. {}", error);
+                        }
+                        break;
+                    }
+                    Err(error) => {
+                        error!("Problem while sending github vulnerability alert query. Query retransmission is done after 5 minutes. {}", error);
+                    }
+                }
+                itv.reset();
+            }
+
+            let mut re_itv = time::interval(retry);
+            loop {
+                re_itv.tick().await;
+                match send_github_code_scanning_alert_query(&repoinfo.owner, &repoinfo.name, &token)
+                    .await
+                {
+                    Ok(resps) => {
+                        if let Err(error) =
+                            db.insert_code_scanning_alerts(resps, &repoinfo.owner, &repoinfo.name)
+                        {
+                            error!("Problem while insert Fjall Database. {}", error);
+                        }
+                        break;
+                    }
+                    Err(error) => {
+                        error!("Problem while sending github code scanning alert query. Query retransmission is done after 5 minutes. {}", error);
+                    }
+                }
+                itv.reset();
+            }
+
+            if let Err(error) =
+                db.insert_daily_snapshot(&repoinfo.owner, &repoinfo.name, Timestamp::now())
+            {
+                error!("Problem while taking a daily snapshot. {}", error);
+            }
+        }
+
+        let mut organizations: Vec<&str> = repositories
+            .iter()
+            .map(|repoinfo| repoinfo.owner.as_str())
+            .collect();
+        organizations.sort_unstable();
+        organizations.dedup();
+        for organization in organizations {
+            let mut re_itv = time::interval(retry);
+            loop {
+                re_itv.tick().await;
+                match send_github_team_query(organization, &token).await {
+                    Ok(resps) => {
+                        if let Err(error) = db.insert_teams(resps, organization) {
+                            error!("Problem while insert Fjall Database. {}", error);
+                        }
+                        break;
+                    }
+                    Err(error) => {
+                        error!("Problem while sending github team query. Query retransmission is done after 5 minutes. {}", error);
+                    }
+                }
+                itv.reset();
+            }
         }
     }
 }
@@ -238,9 +398,41 @@ async fn send_github_pr_query(
                             Some(PullRequestAuthorUser(user)) => user.login,
                             _ => String::new(),
                         },
+                            is_draft: pr.is_draft,
+                            base_ref_name: pr.base_ref_name,
+                            head_ref_name: pr.head_ref_name,
+                            mergeable: pr.mergeable,
+                            merged_by: match pr.merged_by {
+                                Some(PullRequestMergedByUser(user)) => Some(user.login),
+                                _ => None,
+                            },
                             additions: pr.additions.try_into().unwrap_or_default(),
                             deletions: pr.deletions.try_into().unwrap_or_default(),
+                            changed_files: pr.changed_files.try_into().unwrap_or_default(),
+                            files: pr
+                                .files
+                                .and_then(|conn| conn.nodes)
+                                .map(|nodes| {
+                                    nodes.into_iter().flatten().map(|node| node.path).collect()
+                                })
+                                .unwrap_or_default(),
                             url: pr.url,
+                            milestone: pr.milestone.map(|milestone| milestone.title),
+                            closing_issues: pr
+                                .closing_issues_references
+                                .and_then(|conn| conn.nodes)
+                                .map(|nodes| {
+                                    nodes
+                                        .into_iter()
+                                        .flatten()
+                                        .map(|node| ClosingIssueNode {
+                                            owner: node.repository.owner.login,
+                                            repo: node.repository.name,
+                                            number: node.number.try_into().unwrap_or_default(),
+                                        })
+                                        .collect()
+                                })
+                                .unwrap_or_default(),
                             repository: RepositoryNode {
                                 owner: pr.repository.owner.login,
                                 name: pr.repository.name.clone(),
@@ -265,7 +457,8 @@ async fn send_github_pr_query(
                                     .into_iter()
                                     .flatten()
                                     .filter_map(|n| n.as_ref())
-                                    .map(|node| GitHubPRComment {
+                                    .map(|node| {
+                                        Ok(GitHubPRComment {
                                         author: match &node.author {
                                             Some(PRCommentAuthor::User(u)) => u.login.clone(),
                                             _ => String::new(),
@@ -275,9 +468,22 @@ async fn send_github_pr_query(
                                         updated_at: node.updated_at,
                                         repository_name: pr.repository.name.clone(),
                                         url: String::new(),
+                                        reactions: node
+                                            .reaction_groups
+                                            .iter()
+                                            .flatten()
+                                            .map(GitHubReactionGroup::try_from)
+                                            .collect::<Result<_>>()?,
+                                        })
                                     })
-                                    .collect(),
+                                    .collect::<Result<_>>()?,
                             },
+                            reactions: pr
+                                .reaction_groups
+                                .iter()
+                                .flatten()
+                                .map(GitHubReactionGroup::try_from)
+                                .collect::<Result<_>>()?,
 
                             review_decision: pr.review_decision.and_then(|d| match d {
                                 PullRequestReviewDecision::APPROVED => Some(PullRequestReviewState::APPROVED),
@@ -426,6 +632,196 @@ async fn send_github_discussion_query(
     Ok(discussions)
 }
 
+async fn send_github_repository_query(
+    owner: &str,
+    name: &str,
+    token: &str,
+) -> Result<GitHubRepositorySnapshot> {
+    let var = repository_metadata::Variables {
+        owner: owner.to_string(),
+        name: name.to_string(),
+    };
+    let resp_body: GraphQlResponse<repository_metadata::ResponseData> =
+        send_query::<RepositoryMetadata>(token, var)
+            .await?
+            .json()
+            .await?;
+    let repository = resp_body
+        .data
+        .context("You might send wrong request to GitHub.")?
+        .repository
+        .context("No repository was found. Check your request to GitHub.")?;
+    GitHubRepositorySnapshot::try_from(repository)
+}
+
+/// Returns the Dependabot alerts of the repository, or no alerts if the token
+/// cannot access them.
+async fn send_github_vulnerability_alert_query(
+    owner: &str,
+    name: &str,
+    token: &str,
+) -> Result<Vec<GitHubVulnerabilityAlert>> {
+    let mut end_cur: Option<String> = None;
+    let mut alerts = Vec::new();
+    loop {
+        let var = vulnerability_alerts::Variables {
+            owner: owner.to_string(),
+            name: name.to_string(),
+            first: Some(GITHUB_FETCH_SIZE),
+            last: None,
+            before: None,
+            after: end_cur,
+        };
+        let resp_body: GraphQlResponse<vulnerability_alerts::ResponseData> =
+            send_query::<VulnerabilityAlerts>(token, var)
+                .await?
+                .json()
+                .await?;
+        let Some(connection) = resp_body
+            .data
+            .context("You might send wrong request to GitHub.")?
+            .repository
+            .context("No repository was found. Check your request to GitHub.")?
+            .vulnerability_alerts
+        else {
+            warn!("Vulnerability alerts are not accessible for {owner}/{name}.");
+            break;
+        };
+        for alert in connection.nodes.unwrap_or_default().into_iter().flatten() {
+            alerts.push(GitHubVulnerabilityAlert::try_from(alert)?);
+        }
+        if !connection.page_info.has_next_page {
+            break;
+        }
+        end_cur = connection.page_info.end_cursor;
+    }
+    Ok(alerts)
+}
+
+/// Returns the code scanning alerts of the repository, or no alerts if code
+/// scanning is not set up for it or the token cannot access them.
+async fn send_github_code_scanning_alert_query(
+    owner: &str,
+    name: &str,
+    token: &str,
+) -> Result<Vec<GitHubCodeScanningAlert>> {
+    let client = Client::builder().user_agent(APP_USER_AGENT).build()?;
+    let url = format!("{GITHUB_REST_URL}/repos/{owner}/{name}/code-scanning/alerts");
+    let mut alerts = Vec::new();
+    let mut page = 1;
+    loop {
+        let resp = client
+            .get(&url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .query(&[("per_page", GITHUB_REST_PAGE_SIZE), ("page", page)])
+            .send()
+            .await?;
+        if matches!(resp.status(), StatusCode::FORBIDDEN | StatusCode::NOT_FOUND) {
+            warn!("Code scanning alerts are not accessible for {owner}/{name}.");
+            break;
+        }
+        let nodes: Vec<CodeScanningAlertNode> = resp.error_for_status()?.json().await?;
+        let has_next_page = nodes.len() == GITHUB_REST_PAGE_SIZE;
+        for node in nodes {
+            alerts.push(GitHubCodeScanningAlert::try_from(node)?);
+        }
+        if !has_next_page {
+            break;
+        }
+        page += 1;
+    }
+    Ok(alerts)
+}
+
+/// Returns the teams of the organization, or no teams if `login` is a user.
+async fn send_github_team_query(login: &str, token: &str) -> Result<Vec<GitHubTeam>> {
+    let mut end_cur: Option<String> = None;
+    let mut teams = Vec::new();
+    loop {
+        let var = teams::Variables {
+            login: login.to_string(),
+            first: Some(GITHUB_FETCH_SIZE),
+            last: None,
+            before: None,
+            after: end_cur,
+        };
+        let resp_body: GraphQlResponse<teams::ResponseData> =
+            send_query::<Teams>(token, var).await?.json().await?;
+        let Some(organization) = resp_body
+            .data
+            .context("You might send wrong request to GitHub.")?
+            .organization
+        else {
+            break;
+        };
+        for node in organization
+            .teams
+            .nodes
+            .unwrap_or_default()
+            .into_iter()
+            .flatten()
+        {
+            let has_more_members = node.members.page_info.has_next_page;
+            let members_cursor = node.members.page_info.end_cursor.clone();
+            let mut team = GitHubTeam::from(node);
+            if has_more_members {
+                team.members.extend(
+                    send_github_team_member_query(login, &team.slug, members_cursor, token).await?,
+                );
+            }
+            teams.push(team);
+        }
+        if !organization.teams.page_info.has_next_page {
+            break;
+        }
+        end_cur = organization.teams.page_info.end_cursor;
+    }
+    Ok(teams)
+}
+
+/// Returns the logins of the members of the team listed after `after`.
+async fn send_github_team_member_query(
+    login: &str,
+    slug: &str,
+    mut after: Option<String>,
+    token: &str,
+) -> Result<Vec<String>> {
+    let mut members = Vec::new();
+    loop {
+        let var = team_members::Variables {
+            login: login.to_string(),
+            slug: slug.to_string(),
+            first: Some(GITHUB_FETCH_SIZE),
+            after: after.take(),
+        };
+        let resp_body: GraphQlResponse<team_members::ResponseData> =
+            send_query::<TeamMembers>(token, var).await?.json().await?;
+        let Some(connection) = resp_body
+            .data
+            .context("You might send wrong request to GitHub.")?
+            .organization
+            .and_then(|organization| organization.team)
+            .map(|team| team.members)
+        else {
+            break;
+        };
+        members.extend(
+            connection
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|user| user.login),
+        );
+        if !connection.page_info.has_next_page {
+            break;
+        }
+        after = connection.page_info.end_cursor;
+    }
+    Ok(members)
+}
+
 fn request<V>(request_body: &QueryBody<V>, token: &str) -> Result<RequestBuilder>
 where
     V: Serialize,
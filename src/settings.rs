@@ -2,27 +2,46 @@ use std::path::Path;
 use std::{net::SocketAddr, path::PathBuf};
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use config::{builder::DefaultState, ConfigBuilder, ConfigError, File};
+use jiff::civil::Date;
 use serde::{de::Error, Deserialize, Deserializer, Serialize};
 
 const DEFAULT_ADDR: &str = "127.0.0.1:8000";
 const DEFAULT_DATABASE_NAME: &str = "github-dashboard";
 
 #[derive(Parser, Debug)]
-#[command(version)]
+#[command(version, subcommand_negates_reqs = true)]
 pub(crate) struct Args {
     /// Path to the local configuration TOML file.
     #[arg(short, value_name = "CONFIG_PATH")]
     pub(crate) config: PathBuf,
 
     /// Path to the certificate file.
-    #[arg(long, value_name = "CERT_PATH")]
-    pub(crate) cert: PathBuf,
+    #[arg(long, value_name = "CERT_PATH", required = true)]
+    pub(crate) cert: Option<PathBuf>,
 
     /// Path to the key file.
-    #[arg(long, value_name = "KEY_PATH")]
-    pub(crate) key: PathBuf,
+    #[arg(long, value_name = "KEY_PATH", required = true)]
+    pub(crate) key: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+/// A task to run on the database instead of the server.
+#[derive(Subcommand, Debug)]
+pub(crate) enum Command {
+    /// Writes the contents of the database to a JSON Lines file.
+    Export {
+        #[arg(value_name = "OUTPUT_PATH")]
+        path: PathBuf,
+    },
+    /// Restores the contents of the database from a file written by `export`.
+    Import {
+        #[arg(value_name = "INPUT_PATH")]
+        path: PathBuf,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,12 +54,55 @@ pub(crate) struct Web {
 pub(crate) struct Repository {
     pub(crate) owner: String,
     pub(crate) name: String,
+    /// The branch to check out. The default branch of the remote is used if
+    /// this is not set.
+    pub(crate) branch: Option<String>,
+    /// The protocol to clone the repository with. SSH is used if an SSH key is
+    /// configured, and HTTPS otherwise.
+    pub(crate) protocol: Option<CloneProtocol>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CloneProtocol {
+    Ssh,
+    /// HTTPS, authenticated with the GitHub token.
+    Https,
+}
+
+/// A team defined in the configuration file.
+///
+/// Its members are added to the GitHub team with the same slug, or form a new
+/// team if the organization has no such team.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct Team {
+    pub(crate) slug: String,
+    pub(crate) members: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct Certification {
     pub(crate) token: String,
-    pub(crate) ssh: String,
+    pub(crate) ssh: Option<String>,
+}
+
+/// How the local clones are kept.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Checkout {
+    /// The directory to keep the clones in. The cache directory of the
+    /// platform is used if this is not set.
+    pub(crate) base_dir: Option<PathBuf>,
+    /// The number of latest commits to fetch. The whole history is fetched if
+    /// neither this nor `since` is set.
+    pub(crate) depth: Option<u32>,
+    /// The date of the oldest commits to clone. Takes precedence over `depth`.
+    pub(crate) since: Option<Date>,
+    /// The maximum total size of the clones, in MiB. No repository is cloned
+    /// while the clones take more.
+    pub(crate) max_disk_usage_mib: Option<u64>,
+    /// The number of days between runs of `git gc` on the clones. `git gc` is
+    /// run only when the disk usage exceeds its maximum if this is not set.
+    pub(crate) gc_interval_days: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,7 +114,11 @@ pub(crate) struct Database {
 pub(crate) struct Settings {
     pub(crate) web: Web,
     pub(crate) repositories: Vec<Repository>,
+    #[serde(default)]
+    pub(crate) teams: Vec<Team>,
     pub(crate) certification: Certification,
+    #[serde(default)]
+    pub(crate) checkout: Checkout,
     pub(crate) database: Database,
 }
 
@@ -1,11 +1,34 @@
-use std::{convert::Infallible, net::SocketAddr, path::Path};
+use std::{
+    convert::Infallible,
+    io::{self, Write},
+    net::SocketAddr,
+    path::Path,
+};
 
 use async_graphql::http::{playground_source, GraphQLPlaygroundConfig};
-use warp::{http::Response as HttpResponse, Filter};
+use tokio::{runtime::Handle, task};
+use tracing::{error, info};
+use warp::{
+    http::Response as HttpResponse,
+    hyper::{
+        body::{Bytes, Sender},
+        Body,
+    },
+    Filter,
+};
 
-use crate::api::Schema;
+use crate::{api::Schema, database::Database};
 
-pub(super) async fn serve(schema: Schema, socketaddr: SocketAddr, key: &Path, cert: &Path) {
+/// The size of the chunks the body of an export is sent in.
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+pub(super) async fn serve(
+    schema: Schema,
+    database: Database,
+    socketaddr: SocketAddr,
+    key: &Path,
+    cert: &Path,
+) {
     let filter = async_graphql_warp::graphql(schema).and_then(
         |(schema, request): (Schema, async_graphql::Request)| async move {
             let resp = schema.execute(request).await;
@@ -20,10 +43,15 @@ pub(super) async fn serve(schema: Schema, socketaddr: SocketAddr, key: &Path, ce
             .body(playground_source(GraphQLPlaygroundConfig::new("/graphql")))
     });
 
+    let route_export = warp::get()
+        .and(warp::path!("export"))
+        .map(move || export(database.clone()));
     let route_graphql = warp::path("graphql").and(warp::any()).and(filter);
     let route_home = warp::path::end().map(|| "");
 
-    let routes = graphql_playground.or(warp::post().and(route_graphql.or(route_home)));
+    let routes = graphql_playground
+        .or(route_export)
+        .or(warp::post().and(route_graphql.or(route_home)));
 
     warp::serve(routes)
         .tls()
@@ -32,3 +60,61 @@ pub(super) async fn serve(schema: Schema, socketaddr: SocketAddr, key: &Path, ce
         .run(socketaddr)
         .await;
 }
+
+/// Responds with an export of the database, streamed as it is read from a
+/// snapshot, in the format of the `export` subcommand.
+fn export(database: Database) -> Result<HttpResponse<Body>, warp::http::Error> {
+    let (sender, body) = Body::channel();
+    let mut writer = BodyWriter {
+        sender,
+        buffer: Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        runtime: Handle::current(),
+    };
+    task::spawn_blocking(move || match database.export(&mut writer) {
+        Ok(count) => info!("Exported {count} entries"),
+        Err(e) => {
+            error!("Failed to export the database: {e:#}");
+            // Lets the client tell the export is incomplete.
+            writer.sender.abort();
+        }
+    });
+    HttpResponse::builder()
+        .header("content-type", "application/x-ndjson")
+        .body(body)
+}
+
+/// Writes to the body of a response in chunks of `EXPORT_CHUNK_SIZE` bytes.
+struct BodyWriter {
+    sender: Sender,
+    buffer: Vec<u8>,
+    runtime: Handle,
+}
+
+impl BodyWriter {
+    fn send(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(
+            &mut self.buffer,
+            Vec::with_capacity(EXPORT_CHUNK_SIZE),
+        ));
+        self.runtime
+            .block_on(self.sender.send_data(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client disconnected"))
+    }
+}
+
+impl Write for BodyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= EXPORT_CHUNK_SIZE {
+            self.send()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}